@@ -2,6 +2,13 @@ use derive_more::From;
 use serde::{Deserialize, Serialize};
 use storage::persistent::SchemaError;
 
+use crate::chain::block_headers::{
+    ChainBlockHeaderReceivedAction, ChainBlockHeadersDownloadInitAction,
+    ChainBlockHeadersGetAction, ChainBlockHeadersGetNextAction, ChainBlockHeadersGetTimeoutAction,
+};
+use crate::chain::current_head::{
+    ChainCurrentHeadSendAction, ChainPeerCurrentHeadGetAction, ChainPeerCurrentHeadUpdateAction,
+};
 use crate::event::{P2pPeerEvent, P2pServerEvent, WakeupEvent};
 use crate::peer::connection::incoming::accept::*;
 use crate::peer::connection::incoming::PeerConnectionIncomingSuccessAction;
@@ -18,40 +25,62 @@ use crate::peer::connection::outgoing::{
 };
 use crate::peer::disconnection::{PeerDisconnectAction, PeerDisconnectedAction};
 use crate::peer::handshaking::*;
+use crate::peer::message::read::*;
+use crate::peer::message::write::*;
 
 use crate::peer::{PeerTryReadAction, PeerTryWriteAction};
 use crate::peers::add::multi::PeersAddMultiAction;
 use crate::peers::add::PeersAddIncomingPeerAction;
+use crate::peers::advertise::{
+    PeersAdvertiseBootstrapSendAction, PeersAdvertiseReceivedAction, PeersAdvertiseSendAction,
+};
 use crate::peers::dns_lookup::{
-    PeersDnsLookupCleanupAction, PeersDnsLookupErrorAction, PeersDnsLookupInitAction,
+    PeersDnsLookupErrorAction, PeersDnsLookupInitAction, PeersDnsLookupPendingAction,
     PeersDnsLookupSuccessAction,
 };
+use crate::peers::graylist::{PeersGraylistAddressAction, PeersGraylistIpRemoveAction};
 use crate::peers::remove::PeersRemoveAction;
+use crate::peers::timeouts::{PeerTimeoutAction, PeersTimeoutsCheckAction};
 use crate::storage::block_header::put::{
     StorageBlockHeaderPutNextInitAction, StorageBlockHeaderPutNextPendingAction,
     StorageBlockHeadersPutAction,
 };
+use crate::storage::peers::{StoragePeersLoadAction, StoragePeersPutAction};
 use crate::storage::request::{
     StorageRequestCreateAction, StorageRequestErrorAction, StorageRequestFinishAction,
     StorageRequestInitAction, StorageRequestPendingAction, StorageRequestSuccessAction,
 };
 use crate::storage::state_snapshot::create::StorageStateSnapshotCreateAction;
+use crate::tick::TickAction;
 
 #[derive(From, Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type", content = "content")]
 pub enum Action {
+    Tick(TickAction),
+
     PeersDnsLookupInit(PeersDnsLookupInitAction),
+    PeersDnsLookupPending(PeersDnsLookupPendingAction),
     PeersDnsLookupError(PeersDnsLookupErrorAction),
     PeersDnsLookupSuccess(PeersDnsLookupSuccessAction),
-    PeersDnsLookupCleanup(PeersDnsLookupCleanupAction),
 
     PeersAddIncomingPeer(PeersAddIncomingPeerAction),
     PeersAddMulti(PeersAddMultiAction),
     PeersRemove(PeersRemoveAction),
 
+    PeersGraylistAddress(PeersGraylistAddressAction),
+    PeersGraylistIpRemove(PeersGraylistIpRemoveAction),
+
+    PeersAdvertiseBootstrapSend(PeersAdvertiseBootstrapSendAction),
+    PeersAdvertiseSend(PeersAdvertiseSendAction),
+    PeersAdvertiseReceived(PeersAdvertiseReceivedAction),
+
+    PeersTimeoutsCheck(PeersTimeoutsCheckAction),
+    PeerTimeout(PeerTimeoutAction),
+
     PeerConnectionIncomingAccept(PeerConnectionIncomingAcceptAction),
     PeerConnectionIncomingAcceptError(PeerConnectionIncomingAcceptErrorAction),
     PeerConnectionIncomingAcceptSuccess(PeerConnectionIncomingAcceptSuccessAction),
+    PeerConnectionIncomingRejected(PeerConnectionIncomingRejectedAction),
 
     PeerConnectionIncomingSuccess(PeerConnectionIncomingSuccessAction),
 
@@ -123,12 +152,36 @@ pub enum Action {
     PeerHandshakingError(PeerHandshakingErrorAction),
     PeerHandshakingFinish(PeerHandshakingFinishAction),
 
+    // message read
+    PeerMessageReadInit(PeerMessageReadInitAction),
+    PeerMessageReadSuccess(PeerMessageReadSuccessAction),
+    PeerMessageReadError(PeerMessageReadErrorAction),
+
+    // message write
+    PeerMessageWriteInit(PeerMessageWriteInitAction),
+    PeerMessageWriteNext(PeerMessageWriteNextAction),
+    PeerMessageWriteSuccess(PeerMessageWriteSuccessAction),
+    PeerMessageWriteError(PeerMessageWriteErrorAction),
+
+    ChainPeerCurrentHeadGet(ChainPeerCurrentHeadGetAction),
+    ChainPeerCurrentHeadUpdate(ChainPeerCurrentHeadUpdateAction),
+    ChainCurrentHeadSend(ChainCurrentHeadSendAction),
+
+    ChainBlockHeadersDownloadInit(ChainBlockHeadersDownloadInitAction),
+    ChainBlockHeadersGetNext(ChainBlockHeadersGetNextAction),
+    ChainBlockHeadersGet(ChainBlockHeadersGetAction),
+    ChainBlockHeadersGetTimeout(ChainBlockHeadersGetTimeoutAction),
+    ChainBlockHeaderReceived(ChainBlockHeaderReceivedAction),
+
     StorageBlockHeadersPut(StorageBlockHeadersPutAction),
     StorageBlockHeaderPutNextInit(StorageBlockHeaderPutNextInitAction),
     StorageBlockHeaderPutNextPending(StorageBlockHeaderPutNextPendingAction),
 
     StorageStateSnapshotCreate(StorageStateSnapshotCreateAction),
 
+    StoragePeersLoad(StoragePeersLoadAction),
+    StoragePeersPut(StoragePeersPutAction),
+
     StorageRequestCreate(StorageRequestCreateAction),
     StorageRequestInit(StorageRequestInitAction),
     StorageRequestPending(StorageRequestPendingAction),
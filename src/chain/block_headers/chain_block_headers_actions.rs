@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+use crypto::hash::BlockHash;
+use storage::BlockHeaderWithHash;
+
+/// Peer at `address` announced the `head`.
+///
+/// Start downloading block headers down from the `head`, if we
+/// aren't downloading already and `head` is ahead of us. If we are
+/// already downloading the same or a lower head, peer becomes another
+/// source. Higher head is downloaded once the current download finishes.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainBlockHeadersDownloadInitAction {
+    pub address: SocketAddr,
+    pub head: BlockHeaderWithHash,
+}
+
+/// Request the missing block header from one of the download sources,
+/// chosen randomly, weighted by the peer's score.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainBlockHeadersGetNextAction {}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainBlockHeadersGetAction {
+    pub address: SocketAddr,
+    pub hash: BlockHash,
+}
+
+/// Peer didn't send requested block header in time. It stops being
+/// the download source.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainBlockHeadersGetTimeoutAction {
+    pub address: SocketAddr,
+}
+
+/// Requested block header was received. Its hash is already verified.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainBlockHeaderReceivedAction {
+    pub address: SocketAddr,
+    pub block_header: BlockHeaderWithHash,
+}
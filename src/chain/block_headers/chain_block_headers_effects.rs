@@ -0,0 +1,166 @@
+use redux_rs::{ActionWithId, Store};
+
+use tezos_messages::p2p::encoding::block_header::GetBlockHeadersMessage;
+use tezos_messages::p2p::encoding::peer::PeerMessage;
+
+use crate::chain::block_header_with_hash;
+use crate::peer::message::write::PeerMessageWriteInitAction;
+use crate::service::{RandomnessService, Service};
+use crate::storage::block_header::put::StorageBlockHeadersPutAction;
+use crate::{action::Action, State};
+
+use super::{
+    ChainBlockHeaderReceivedAction, ChainBlockHeadersDownloadInitAction,
+    ChainBlockHeadersGetAction, ChainBlockHeadersGetNextAction, ChainBlockHeadersGetTimeoutAction,
+};
+
+/// Start the download from the highest head of the peers ahead of us.
+fn chain_block_headers_download_restart<S>(store: &mut Store<State, S, Action>)
+where
+    S: Service,
+{
+    let mut peers_ahead = store
+        .state
+        .get()
+        .chain
+        .peers_ahead()
+        .map(|(address, peer_head)| (*address, peer_head.head.clone()))
+        .collect::<Vec<_>>();
+    peers_ahead.sort_by_key(|(_, head)| std::cmp::Reverse(head.header.level()));
+
+    for (address, head) in peers_ahead {
+        store.dispatch(ChainBlockHeadersDownloadInitAction { address, head }.into());
+    }
+}
+
+pub fn chain_block_headers_effects<S>(
+    store: &mut Store<State, S, Action>,
+    action: &ActionWithId<Action>,
+) where
+    S: Service,
+{
+    match &action.action {
+        Action::ChainPeerCurrentHeadUpdate(action) => {
+            store.dispatch(
+                ChainBlockHeadersDownloadInitAction {
+                    address: action.address,
+                    head: action.head.clone(),
+                }
+                .into(),
+            );
+        }
+        Action::ChainBlockHeadersDownloadInit(_) => {
+            store.dispatch(ChainBlockHeadersGetNextAction {}.into());
+        }
+        Action::PeerDisconnected(_) => {
+            if store.state.get().chain.block_headers.download.is_some() {
+                store.dispatch(ChainBlockHeadersGetNextAction {}.into());
+            } else {
+                // all sources of the download have disconnected.
+                chain_block_headers_download_restart(store);
+            }
+        }
+        Action::Tick(_) => {
+            let state = store.state.get();
+            let timeout = state.config.chain_block_headers_get_timeout;
+            let timed_out = state
+                .chain
+                .block_headers
+                .requests
+                .iter()
+                .filter(|(_, request)| state.time >= request.sent_at + timeout)
+                .map(|(address, _)| *address)
+                .collect::<Vec<_>>();
+
+            for address in timed_out {
+                store.dispatch(ChainBlockHeadersGetTimeoutAction { address }.into());
+            }
+        }
+        Action::ChainBlockHeadersGetTimeout(_) => {
+            if store.state.get().chain.block_headers.download.is_some() {
+                store.dispatch(ChainBlockHeadersGetNextAction {}.into());
+            } else {
+                // all sources have timed out.
+                chain_block_headers_download_restart(store);
+            }
+        }
+        Action::ChainBlockHeadersGetNext(_) => {
+            let state = store.state.get();
+            let download = match &state.chain.block_headers.download {
+                Some(v) => v,
+                None => return,
+            };
+            let hash = match &download.missing {
+                Some(v) => v.clone(),
+                None => return,
+            };
+            // only peers, which announced the head, are asked for its
+            // predecessors.
+            let sources = download
+                .sources
+                .iter()
+                .map(|address| (*address, state.peer_score(address)))
+                .collect::<Vec<_>>();
+            let address = match store.service.randomness().choose_peer(&sources) {
+                Some(v) => v,
+                None => return,
+            };
+            store.dispatch(ChainBlockHeadersGetAction { address, hash }.into());
+        }
+        Action::ChainBlockHeadersGet(action) => {
+            store.dispatch(
+                PeerMessageWriteInitAction {
+                    address: action.address,
+                    message: PeerMessage::GetBlockHeaders(GetBlockHeadersMessage::new(vec![
+                        action.hash.clone(),
+                    ]))
+                    .into(),
+                }
+                .into(),
+            );
+        }
+        Action::PeerMessageReadSuccess(action) => {
+            let message = match action.message.message() {
+                PeerMessage::BlockHeader(v) => v,
+                _ => return,
+            };
+            let block_header = match block_header_with_hash(message.block_header().clone()) {
+                Some(v) => v,
+                None => return,
+            };
+            // only accept block headers we asked for.
+            if !store
+                .state
+                .get()
+                .chain
+                .block_headers
+                .is_requested(&action.address, &block_header.hash)
+            {
+                return;
+            }
+            store.dispatch(
+                ChainBlockHeaderReceivedAction {
+                    address: action.address,
+                    block_header,
+                }
+                .into(),
+            );
+        }
+        Action::ChainBlockHeaderReceived(action) => {
+            store.dispatch(
+                StorageBlockHeadersPutAction {
+                    block_headers: vec![action.block_header.clone()],
+                }
+                .into(),
+            );
+            if store.state.get().chain.block_headers.download.is_some() {
+                store.dispatch(ChainBlockHeadersGetNextAction {}.into());
+            } else {
+                // download is finished. Continue with the higher heads,
+                // announced in the meantime.
+                chain_block_headers_download_restart(store);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,119 @@
+use redux_rs::ActionWithId;
+use std::net::SocketAddr;
+
+use crate::{action::Action, State};
+
+use super::{ChainBlockHeadersDownload, ChainBlockHeadersRequest};
+
+/// Cancel outstanding request to the peer and mark its hash as missing
+/// again. Peer stops being the download source.
+fn chain_block_headers_source_remove(state: &mut State, address: &SocketAddr) {
+    let block_headers = &mut state.chain.block_headers;
+    let request = block_headers.requests.remove(address);
+    let download = match &mut block_headers.download {
+        Some(v) => v,
+        None => return,
+    };
+    if let Some(request) = request {
+        if download.missing.is_none() {
+            download.missing = Some(request.hash);
+        }
+    }
+    download.sources.remove(address);
+    if download.sources.is_empty() {
+        block_headers.download = None;
+        block_headers.requests.clear();
+    }
+}
+
+pub fn chain_block_headers_reducer(state: &mut State, action: &ActionWithId<Action>) {
+    match &action.action {
+        Action::ChainBlockHeadersDownloadInit(action) => {
+            let stop_level = state.chain.current_head_level().unwrap_or(0);
+            let block_headers = &mut state.chain.block_headers;
+            if let Some(download) = &mut block_headers.download {
+                // peer with the higher head has the headers we are
+                // downloading, unless it's on a fork, in which case it
+                // will time out.
+                if download.head.hash == action.head.hash
+                    || download.head.header.level() < action.head.header.level()
+                {
+                    download.sources.insert(action.address);
+                }
+                return;
+            }
+            if action.head.header.level() <= stop_level {
+                return;
+            }
+            block_headers.download = Some(ChainBlockHeadersDownload {
+                head: action.head.clone(),
+                stop_level,
+                sources: std::iter::once(action.address).collect(),
+                missing: Some(action.head.hash.clone()),
+            });
+        }
+        Action::ChainBlockHeadersGet(action) => {
+            let sent_at = state.time;
+            let block_headers = &mut state.chain.block_headers;
+            if let Some(download) = &mut block_headers.download {
+                if download.missing.as_ref() == Some(&action.hash) {
+                    download.missing = None;
+                }
+            }
+            block_headers.requests.insert(
+                action.address,
+                ChainBlockHeadersRequest {
+                    hash: action.hash.clone(),
+                    sent_at,
+                },
+            );
+        }
+        Action::ChainBlockHeadersGetTimeout(action) => {
+            chain_block_headers_source_remove(state, &action.address);
+            // peer didn't serve the head it announced, so it's no longer
+            // considered ahead of us, until it announces a new one.
+            state.chain.peers_heads.remove(&action.address);
+        }
+        Action::PeerDisconnected(action) => {
+            chain_block_headers_source_remove(state, &action.address);
+        }
+        Action::ChainBlockHeaderReceived(action) => {
+            let chain = &mut state.chain;
+            if !chain
+                .block_headers
+                .is_requested(&action.address, &action.block_header.hash)
+            {
+                return;
+            }
+            chain.block_headers.requests.remove(&action.address);
+
+            let download = match &mut chain.block_headers.download {
+                Some(v) => v,
+                None => return,
+            };
+            let header = &action.block_header.header;
+            if header.level() - 1 > download.stop_level {
+                download.missing = Some(header.predecessor().clone());
+                return;
+            }
+            // Forks aren't handled yet, so the download which doesn't
+            // lead to our current head is dropped, along with the peers'
+            // heads it was started from, so that it isn't restarted.
+            let is_fork = chain
+                .current_head
+                .as_ref()
+                .map_or(false, |head| &head.hash != header.predecessor());
+            if is_fork {
+                let fork_hash = &download.head.hash;
+                chain
+                    .peers_heads
+                    .retain(|_, peer_head| &peer_head.head.hash != fork_hash);
+            } else {
+                chain.current_head = Some(download.head.clone());
+            }
+            chain.block_headers.download = None;
+            chain.block_headers.requests.clear();
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::SocketAddr;
+use std::time::SystemTime;
+
+use crypto::hash::BlockHash;
+use storage::BlockHeaderWithHash;
+
+/// Download of the block headers from the peer's head, down to our
+/// current head.
+///
+/// Headers are downloaded backwards, by following predecessors, so
+/// only one header can be requested at a time.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainBlockHeadersDownload {
+    pub head: BlockHeaderWithHash,
+    /// Level of our current head when download started. Download is
+    /// finished once we reach it.
+    pub stop_level: i32,
+    /// Peers which announced the `head` (or a higher one). Only they
+    /// are asked for the block headers. Peer stops being a source once
+    /// it disconnects or times out and download is dropped once there
+    /// are no sources left.
+    pub sources: BTreeSet<SocketAddr>,
+    /// Hash of the next block header to request. `None` while it's
+    /// requested.
+    pub missing: Option<BlockHash>,
+}
+
+/// Outstanding `GetBlockHeadersMessage` sent to the peer.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainBlockHeadersRequest {
+    pub hash: BlockHash,
+    /// Request times out after `config.chain_block_headers_get_timeout`
+    /// since this time.
+    pub sent_at: SystemTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ChainBlockHeadersState {
+    pub download: Option<ChainBlockHeadersDownload>,
+    /// Outstanding requests by the peer's address.
+    pub requests: BTreeMap<SocketAddr, ChainBlockHeadersRequest>,
+}
+
+impl ChainBlockHeadersState {
+    /// Whether block header was requested from the peer.
+    pub fn is_requested(&self, address: &SocketAddr, hash: &BlockHash) -> bool {
+        self.requests
+            .get(address)
+            .map_or(false, |request| &request.hash == hash)
+    }
+}
@@ -0,0 +1,11 @@
+mod chain_block_headers_state;
+pub use chain_block_headers_state::*;
+
+mod chain_block_headers_actions;
+pub use chain_block_headers_actions::*;
+
+mod chain_block_headers_reducer;
+pub use chain_block_headers_reducer::*;
+
+mod chain_block_headers_effects;
+pub use chain_block_headers_effects::*;
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::convert::{TryFrom, TryInto};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crypto::hash::{BlockHash, ContextHash, OperationListListHash};
+use storage::BlockHeaderWithHash;
+use tezos_messages::p2p::binary_message::MessageHash;
+use tezos_messages::p2p::encoding::block_header::{BlockHeader, BlockHeaderBuilder};
+
+use super::block_headers::ChainBlockHeadersState;
+
+/// Current head reported by the peer.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainPeerCurrentHead {
+    pub head: BlockHeaderWithHash,
+    pub updated_at: SystemTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainState {
+    /// Our current head. Head of the last finished block headers
+    /// download.
+    ///
+    /// `None` if we haven't downloaded any block headers yet.
+    pub current_head: Option<BlockHeaderWithHash>,
+    /// Current heads of the handshaked peers.
+    pub peers_heads: BTreeMap<SocketAddr, ChainPeerCurrentHead>,
+    pub block_headers: ChainBlockHeadersState,
+}
+
+impl ChainState {
+    pub fn new() -> Self {
+        Self {
+            current_head: None,
+            peers_heads: BTreeMap::new(),
+            block_headers: ChainBlockHeadersState::default(),
+        }
+    }
+
+    pub fn current_head_level(&self) -> Option<i32> {
+        self.current_head.as_ref().map(|head| head.header.level())
+    }
+
+    /// Peers, whose current head is higher (by level) than ours. Until
+    /// we have a head, we are at genesis (level 0).
+    pub fn peers_ahead(&self) -> impl Iterator<Item = (&SocketAddr, &ChainPeerCurrentHead)> {
+        let level = self.current_head_level().unwrap_or(0);
+        self.peers_heads
+            .iter()
+            .filter(move |(_, peer_head)| peer_head.head.header.level() > level)
+    }
+}
+
+/// Compute block header's hash.
+pub fn block_header_hash(header: &BlockHeader) -> Option<BlockHash> {
+    header.message_hash().ok()?.try_into().ok()
+}
+
+pub fn block_header_with_hash(header: BlockHeader) -> Option<BlockHeaderWithHash> {
+    Some(BlockHeaderWithHash {
+        hash: block_header_hash(&header)?,
+        header: Arc::new(header),
+    })
+}
+
+/// Genesis block header, which we report as our current head, until we
+/// download any block headers.
+///
+/// We don't have the chain's storage yet, so only the level and the
+/// hash are real, which is all the peers look at.
+pub fn genesis_block_header(hash: BlockHash) -> BlockHeaderWithHash {
+    let header = BlockHeaderBuilder::default()
+        .level(0)
+        .proto(0)
+        .predecessor(hash.clone())
+        .timestamp(0)
+        .validation_pass(0)
+        .operations_hash(OperationListListHash::try_from(vec![0; 32]).unwrap())
+        .fitness(vec![])
+        .context(ContextHash::try_from(vec![0; 32]).unwrap())
+        .protocol_data(vec![])
+        .build()
+        .unwrap();
+    BlockHeaderWithHash {
+        hash,
+        header: Arc::new(header),
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+use storage::BlockHeaderWithHash;
+
+/// Ask the handshaked peer for its current head.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainPeerCurrentHeadGetAction {
+    pub address: SocketAddr,
+}
+
+/// Peer sent us its current head.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainPeerCurrentHeadUpdateAction {
+    pub address: SocketAddr,
+    pub head: BlockHeaderWithHash,
+}
+
+/// Send our current head to the peer, which asked for it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainCurrentHeadSendAction {
+    pub address: SocketAddr,
+}
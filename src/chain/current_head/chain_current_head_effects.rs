@@ -0,0 +1,104 @@
+use redux_rs::{ActionWithId, Store};
+
+use tezos_messages::p2p::encoding::current_head::{CurrentHeadMessage, GetCurrentHeadMessage};
+use tezos_messages::p2p::encoding::mempool::Mempool;
+use tezos_messages::p2p::encoding::peer::PeerMessage;
+
+use crate::chain::{block_header_with_hash, genesis_block_header};
+use crate::peer::message::write::PeerMessageWriteInitAction;
+use crate::peer::PeerStatus;
+use crate::service::Service;
+use crate::{action::Action, State};
+
+use super::{
+    ChainCurrentHeadSendAction, ChainPeerCurrentHeadGetAction, ChainPeerCurrentHeadUpdateAction,
+};
+
+pub fn chain_current_head_effects<S>(
+    store: &mut Store<State, S, Action>,
+    action: &ActionWithId<Action>,
+) where
+    S: Service,
+{
+    match &action.action {
+        Action::PeerHandshakingFinish(action) => {
+            let is_handshaked = store
+                .state
+                .get()
+                .peers
+                .get(&action.address)
+                .map_or(false, |peer| {
+                    matches!(&peer.status, PeerStatus::Handshaked(_))
+                });
+            if is_handshaked {
+                store.dispatch(
+                    ChainPeerCurrentHeadGetAction {
+                        address: action.address,
+                    }
+                    .into(),
+                );
+            }
+        }
+        Action::ChainPeerCurrentHeadGet(action) => {
+            let chain_id = store.state.get().config.chain_id.clone();
+            store.dispatch(
+                PeerMessageWriteInitAction {
+                    address: action.address,
+                    message: PeerMessage::GetCurrentHead(GetCurrentHeadMessage::new(chain_id))
+                        .into(),
+                }
+                .into(),
+            );
+        }
+        Action::PeerMessageReadSuccess(action) => {
+            let chain_id = &store.state.get().config.chain_id;
+            match action.message.message() {
+                PeerMessage::GetCurrentHead(message) if message.chain_id() == chain_id => {
+                    store.dispatch(
+                        ChainCurrentHeadSendAction {
+                            address: action.address,
+                        }
+                        .into(),
+                    );
+                }
+                PeerMessage::CurrentHead(message) if message.chain_id() == chain_id => {
+                    let head = match block_header_with_hash(message.current_block_header().clone())
+                    {
+                        Some(v) => v,
+                        None => return,
+                    };
+                    store.dispatch(
+                        ChainPeerCurrentHeadUpdateAction {
+                            address: action.address,
+                            head,
+                        }
+                        .into(),
+                    );
+                }
+                _ => {}
+            }
+        }
+        Action::ChainCurrentHeadSend(action) => {
+            let state = store.state.get();
+            // peer is waiting for the reply, so if we don't have a head
+            // yet, we report genesis.
+            let head = match &state.chain.current_head {
+                Some(v) => v.clone(),
+                None => genesis_block_header(state.config.genesis_block_hash.clone()),
+            };
+            let message = CurrentHeadMessage::new(
+                state.config.chain_id.clone(),
+                (*head.header).clone(),
+                Mempool::default(),
+            );
+            store.dispatch(
+                PeerMessageWriteInitAction {
+                    address: action.address,
+                    message: PeerMessage::CurrentHead(message).into(),
+                }
+                .into(),
+            );
+        }
+        _ => {}
+    }
+}
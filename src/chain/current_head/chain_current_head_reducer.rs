@@ -0,0 +1,30 @@
+use redux_rs::ActionWithId;
+
+use crate::chain::ChainPeerCurrentHead;
+use crate::peer::PeerStatus;
+use crate::{action::Action, State};
+
+pub fn chain_current_head_reducer(state: &mut State, action: &ActionWithId<Action>) {
+    match &action.action {
+        Action::ChainPeerCurrentHeadUpdate(action) => {
+            let is_handshaked = state.peers.get(&action.address).map_or(false, |peer| {
+                matches!(&peer.status, PeerStatus::Handshaked(_))
+            });
+            if !is_handshaked {
+                return;
+            }
+            let updated_at = state.time;
+            state.chain.peers_heads.insert(
+                action.address,
+                ChainPeerCurrentHead {
+                    head: action.head.clone(),
+                    updated_at,
+                },
+            );
+        }
+        Action::PeerDisconnected(action) => {
+            state.chain.peers_heads.remove(&action.address);
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,8 @@
+mod chain_current_head_actions;
+pub use chain_current_head_actions::*;
+
+mod chain_current_head_reducer;
+pub use chain_current_head_reducer::*;
+
+mod chain_current_head_effects;
+pub use chain_current_head_effects::*;
@@ -0,0 +1,5 @@
+mod chain_state;
+pub use chain_state::*;
+
+pub mod block_headers;
+pub mod current_head;
@@ -0,0 +1,625 @@
+//! Command line interface of the node.
+//!
+//! [Config] is built from (in the order of precedence):
+//! 1. command line arguments.
+//! 2. config file (TOML or JSON), passed with `--config-file`.
+//! 3. defaults of the chosen network preset ([default_config]).
+//!
+//! `replay` subcommand runs [crate::replay] instead of the node.
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::config::{default_config, Config, TezosNetwork, TrustedPeer};
+use crate::identity::{identity_load_or_generate, IdentityLoadError};
+use crate::Port;
+use crypto::hash::CryptoboxPublicKeyHash;
+
+const DEFAULT_BOOTSTRAP_PEER_PORT: Port = 9732;
+const IDENTITY_FILE_NAME: &str = "identity.json";
+
+#[derive(Debug)]
+pub enum ConfigLoadError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    Identity(IdentityLoadError),
+    InvalidValue { name: String, value: String },
+}
+
+impl From<io::Error> for ConfigLoadError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<toml::de::Error> for ConfigLoadError {
+    fn from(error: toml::de::Error) -> Self {
+        Self::Toml(error)
+    }
+}
+
+impl From<serde_json::Error> for ConfigLoadError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+impl From<IdentityLoadError> for ConfigLoadError {
+    fn from(error: IdentityLoadError) -> Self {
+        Self::Identity(error)
+    }
+}
+
+/// Contents of the config file. Every field is optional.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    pub network: Option<TezosNetwork>,
+    pub p2p_address: Option<SocketAddr>,
+    pub rpc_address: Option<SocketAddr>,
+    /// Bootstrap peers in `host[:port]` or `ip:port` format.
+    pub bootstrap_peers: Option<Vec<String>>,
+    /// Trusted peers in `[peer_id@]ip:port` format.
+    pub trusted_peers: Option<Vec<String>>,
+    pub data_dir: Option<PathBuf>,
+    /// Defaults to `identity.json` inside `data_dir`.
+    pub identity_file: Option<PathBuf>,
+    pub pow_target: Option<f64>,
+    /// Randomness is OS seeded if not set.
+    pub randomness_seed: Option<u64>,
+    pub disable_mempool: Option<bool>,
+    pub private_node: Option<bool>,
+    pub peers_potential_min: Option<usize>,
+    pub peers_potential_max: Option<usize>,
+    pub peers_connected_min: Option<usize>,
+    pub peers_connected_max: Option<usize>,
+    pub peers_incoming_max: Option<usize>,
+    pub peers_pending_max: Option<usize>,
+    pub state_snapshot_interval: Option<u64>,
+}
+
+impl ConfigFile {
+    /// Parse config file. Format is chosen based on file extension.
+    pub fn load(path: &Path) -> Result<Self, ConfigLoadError> {
+        let contents = fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Ok(serde_json::from_str(&contents)?),
+            _ => Ok(toml::from_str(&contents)?),
+        }
+    }
+
+    /// Override values with the ones passed as command line arguments.
+    fn merge_args(&mut self, args: &ArgMatches) -> Result<(), ConfigLoadError> {
+        fn set<T: FromStr>(
+            field: &mut Option<T>,
+            args: &ArgMatches,
+            name: &str,
+        ) -> Result<(), ConfigLoadError> {
+            if let Some(value) = args.value_of(name) {
+                *field = Some(value.parse().map_err(|_| ConfigLoadError::InvalidValue {
+                    name: name.to_owned(),
+                    value: value.to_owned(),
+                })?);
+            }
+            Ok(())
+        }
+
+        set(&mut self.network, args, "network")?;
+        set(&mut self.p2p_address, args, "p2p-address")?;
+        set(&mut self.rpc_address, args, "rpc-address")?;
+        set(&mut self.data_dir, args, "data-dir")?;
+        set(&mut self.identity_file, args, "identity-file")?;
+        set(&mut self.pow_target, args, "pow-target")?;
+        set(&mut self.randomness_seed, args, "randomness-seed")?;
+        set(&mut self.peers_potential_min, args, "peers-potential-min")?;
+        set(&mut self.peers_potential_max, args, "peers-potential-max")?;
+        set(&mut self.peers_connected_min, args, "peers-connected-min")?;
+        set(&mut self.peers_connected_max, args, "peers-connected-max")?;
+        set(&mut self.peers_incoming_max, args, "peers-incoming-max")?;
+        set(&mut self.peers_pending_max, args, "peers-pending-max")?;
+        set(
+            &mut self.state_snapshot_interval,
+            args,
+            "state-snapshot-interval",
+        )?;
+
+        if let Some(values) = args.values_of("bootstrap-peers") {
+            self.bootstrap_peers = Some(values.map(|v| v.to_owned()).collect());
+        }
+        if let Some(values) = args.values_of("trusted-peers") {
+            self.trusted_peers = Some(values.map(|v| v.to_owned()).collect());
+        }
+        if args.is_present("disable-mempool") {
+            self.disable_mempool = Some(true);
+        }
+        if args.is_present("private-node") {
+            self.private_node = Some(true);
+        }
+        Ok(())
+    }
+
+    pub fn data_dir(&self) -> PathBuf {
+        self.data_dir
+            .clone()
+            .unwrap_or_else(|| default_config().data_dir)
+    }
+
+    pub fn into_config(self) -> Result<Config, ConfigLoadError> {
+        let defaults = default_config();
+        let network = self.network.unwrap_or(TezosNetwork::Mainnet);
+
+        let (bootstrap_peers, bootstrap_addresses) = match self.bootstrap_peers {
+            Some(peers) => {
+                let mut names = vec![];
+                let mut addresses = vec![];
+                for peer in peers.iter() {
+                    match parse_bootstrap_peer(peer)? {
+                        BootstrapPeer::Name(address, port) => names.push((address, port)),
+                        BootstrapPeer::Address(address) => addresses.push(address),
+                    }
+                }
+                (names, addresses)
+            }
+            None => (network.bootstrap_peers(), vec![]),
+        };
+
+        let trusted_peers = match self.trusted_peers {
+            Some(peers) => peers
+                .iter()
+                .map(|peer| parse_trusted_peer(peer))
+                .collect::<Result<_, _>>()?,
+            None => defaults.trusted_peers,
+        };
+
+        let data_dir = self.data_dir.unwrap_or(defaults.data_dir);
+        let pow_target = self.pow_target.unwrap_or(defaults.pow_target);
+        let identity_file = self
+            .identity_file
+            .unwrap_or_else(|| data_dir.join(IDENTITY_FILE_NAME));
+        let identity = identity_load_or_generate(&identity_file, pow_target)?;
+
+        Ok(Config {
+            p2p_address: self.p2p_address.unwrap_or(defaults.p2p_address),
+            rpc_address: self.rpc_address.unwrap_or(defaults.rpc_address),
+            bootstrap_peers,
+            bootstrap_addresses,
+            trusted_peers,
+            data_dir,
+            disable_mempool: self.disable_mempool.unwrap_or(defaults.disable_mempool),
+            private_node: self.private_node.unwrap_or(defaults.private_node),
+            pow_target,
+            randomness_seed: self.randomness_seed.or(defaults.randomness_seed),
+            identity,
+            shell_compatibility_version: network.shell_compatibility_version(),
+            chain_id: network.chain_id(),
+            genesis_block_hash: network.genesis_block_hash(),
+            peers_potential_min: self
+                .peers_potential_min
+                .unwrap_or(defaults.peers_potential_min),
+            peers_potential_max: self
+                .peers_potential_max
+                .unwrap_or(defaults.peers_potential_max),
+            peers_connected_min: self
+                .peers_connected_min
+                .unwrap_or(defaults.peers_connected_min),
+            peers_connected_max: self
+                .peers_connected_max
+                .unwrap_or(defaults.peers_connected_max),
+            peers_incoming_max: self
+                .peers_incoming_max
+                .unwrap_or(defaults.peers_incoming_max),
+            peers_pending_max: self.peers_pending_max.unwrap_or(defaults.peers_pending_max),
+            state_snapshot_interval: self
+                .state_snapshot_interval
+                .unwrap_or(defaults.state_snapshot_interval)
+                .max(1),
+            ..defaults
+        })
+    }
+}
+
+enum BootstrapPeer {
+    Name(String, Port),
+    Address(SocketAddr),
+}
+
+/// Parse bootstrap peer in `host[:port]` or `ip[:port]` format.
+fn parse_bootstrap_peer(peer: &str) -> Result<BootstrapPeer, ConfigLoadError> {
+    if let Ok(address) = peer.parse() {
+        return Ok(BootstrapPeer::Address(address));
+    }
+    if let Ok(ip) = peer.parse::<IpAddr>() {
+        return Ok(BootstrapPeer::Address(SocketAddr::new(
+            ip,
+            DEFAULT_BOOTSTRAP_PEER_PORT,
+        )));
+    }
+    match peer.rsplit_once(':') {
+        Some((host, port)) => match port.parse() {
+            Ok(port) => Ok(BootstrapPeer::Name(host.to_owned(), port)),
+            Err(_) => Err(ConfigLoadError::InvalidValue {
+                name: "bootstrap-peers".to_owned(),
+                value: peer.to_owned(),
+            }),
+        },
+        None => Ok(BootstrapPeer::Name(
+            peer.to_owned(),
+            DEFAULT_BOOTSTRAP_PEER_PORT,
+        )),
+    }
+}
+
+/// Parse trusted peer in `[peer_id@]ip:port` format.
+fn parse_trusted_peer(peer: &str) -> Result<TrustedPeer, ConfigLoadError> {
+    let invalid = || ConfigLoadError::InvalidValue {
+        name: "trusted-peers".to_owned(),
+        value: peer.to_owned(),
+    };
+    let (peer_id, address) = match peer.split_once('@') {
+        Some((peer_id, address)) => (
+            Some(CryptoboxPublicKeyHash::from_base58_check(peer_id).map_err(|_| invalid())?),
+            address,
+        ),
+        None => (None, peer),
+    };
+    Ok(TrustedPeer {
+        address: address.parse().map_err(|_| invalid())?,
+        peer_id,
+    })
+}
+
+/// Arguments of the `replay` subcommand.
+#[derive(Debug, Clone, Copy)]
+pub struct ReplayArgs {
+    /// Action id of the state snapshot to start from.
+    pub from: u64,
+    /// Last action id to replay. Replay till the end of the action log if `None`.
+    pub to: Option<u64>,
+}
+
+impl ReplayArgs {
+    fn from_args(args: &ArgMatches) -> Result<Self, ConfigLoadError> {
+        let parse = |name: &str| {
+            args.value_of(name)
+                .map(|value| {
+                    value.parse().map_err(|_| ConfigLoadError::InvalidValue {
+                        name: name.to_owned(),
+                        value: value.to_owned(),
+                    })
+                })
+                .transpose()
+        };
+        Ok(Self {
+            from: parse("from")?.unwrap_or(0),
+            to: parse("to")?,
+        })
+    }
+}
+
+fn app() -> App<'static, 'static> {
+    App::new("tezedge_redux")
+        .version(env!("CARGO_PKG_VERSION"))
+        .arg(
+            Arg::with_name("config-file")
+                .long("config-file")
+                .takes_value(true)
+                .value_name("PATH")
+                .help("Path to the config file (TOML, or JSON if extension is `.json`)"),
+        )
+        .arg(
+            Arg::with_name("network")
+                .long("network")
+                .takes_value(true)
+                .possible_values(&["mainnet", "granadanet"])
+                .help("Network preset"),
+        )
+        .arg(
+            Arg::with_name("p2p-address")
+                .long("p2p-address")
+                .takes_value(true)
+                .value_name("IP:PORT")
+                .help("Address to listen on for incoming p2p connections"),
+        )
+        .arg(
+            Arg::with_name("rpc-address")
+                .long("rpc-address")
+                .takes_value(true)
+                .value_name("IP:PORT")
+                .help("Address to listen on for rpc requests"),
+        )
+        .arg(
+            Arg::with_name("bootstrap-peers")
+                .long("bootstrap-peers")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .value_name("HOST[:PORT]")
+                .help("Comma separated list of bootstrap peers (dns names or ip addresses)"),
+        )
+        .arg(
+            Arg::with_name("trusted-peers")
+                .long("trusted-peers")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .value_name("[PEER_ID@]IP:PORT")
+                .help("Comma separated list of trusted peers, which are always kept connected"),
+        )
+        .arg(
+            Arg::with_name("data-dir")
+                .long("data-dir")
+                .takes_value(true)
+                .value_name("PATH")
+                .help("Directory where the database is stored"),
+        )
+        .arg(
+            Arg::with_name("identity-file")
+                .long("identity-file")
+                .takes_value(true)
+                .value_name("PATH")
+                .help("Path to the identity json file. Generated if it doesn't exist"),
+        )
+        .arg(
+            Arg::with_name("pow-target")
+                .long("pow-target")
+                .takes_value(true)
+                .help("Required proof of work of the peers' identities"),
+        )
+        .arg(
+            Arg::with_name("randomness-seed")
+                .long("randomness-seed")
+                .takes_value(true)
+                .help(
+                    "Seed for deterministic randomness (nonces, peer choice). OS seeded if not set",
+                ),
+        )
+        .arg(
+            Arg::with_name("disable-mempool")
+                .long("disable-mempool")
+                .help("Don't exchange mempool with peers"),
+        )
+        .arg(
+            Arg::with_name("private-node")
+                .long("private-node")
+                .help("Run node in private mode (only connect to trusted peers)"),
+        )
+        .arg(
+            Arg::with_name("peers-potential-min")
+                .long("peers-potential-min")
+                .takes_value(true)
+                .help(
+                    "Low threshold for potential peers, below which bootstrap peers are used again",
+                ),
+        )
+        .arg(
+            Arg::with_name("peers-potential-max")
+                .long("peers-potential-max")
+                .takes_value(true)
+                .help("Max number of potential peers kept in state"),
+        )
+        .arg(
+            Arg::with_name("peers-connected-min")
+                .long("peers-connected-min")
+                .takes_value(true)
+                .help("Low threshold for connected peers"),
+        )
+        .arg(
+            Arg::with_name("peers-connected-max")
+                .long("peers-connected-max")
+                .takes_value(true)
+                .help("High threshold for connected peers"),
+        )
+        .arg(
+            Arg::with_name("peers-incoming-max")
+                .long("peers-incoming-max")
+                .takes_value(true)
+                .help("Max number of peers connected to us"),
+        )
+        .arg(
+            Arg::with_name("peers-pending-max")
+                .long("peers-pending-max")
+                .takes_value(true)
+                .help("Max number of connecting/handshaking peers"),
+        )
+        .arg(
+            Arg::with_name("state-snapshot-interval")
+                .long("state-snapshot-interval")
+                .takes_value(true)
+                .help("Number of actions between persisted state snapshots"),
+        )
+        .subcommand(
+            SubCommand::with_name("replay")
+                .about("Replay persisted actions and compare results with state snapshots")
+                .arg(
+                    Arg::with_name("from")
+                        .long("from")
+                        .takes_value(true)
+                        .value_name("ACTION_ID")
+                        .required(true)
+                        .help("Action id of the state snapshot to start from"),
+                )
+                .arg(
+                    Arg::with_name("to")
+                        .long("to")
+                        .takes_value(true)
+                        .value_name("ACTION_ID")
+                        .help("Last action id to replay"),
+                ),
+        )
+}
+
+pub fn args() -> ArgMatches<'static> {
+    app().get_matches()
+}
+
+/// Load config file and override its values with command line arguments.
+pub fn config_file_from_args(args: &ArgMatches) -> Result<ConfigFile, ConfigLoadError> {
+    let mut config_file = match args.value_of("config-file") {
+        Some(path) => ConfigFile::load(Path::new(path))?,
+        None => ConfigFile::default(),
+    };
+    config_file.merge_args(args)?;
+    Ok(config_file)
+}
+
+/// Parse `replay` subcommand arguments, if node was started with it.
+pub fn replay_args(args: &ArgMatches) -> Result<Option<ReplayArgs>, ConfigLoadError> {
+    args.subcommand_matches("replay")
+        .map(ReplayArgs::from_args)
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+
+    fn bootstrap_name(peer: &str) -> (String, Port) {
+        match parse_bootstrap_peer(peer).unwrap() {
+            BootstrapPeer::Name(host, port) => (host, port),
+            BootstrapPeer::Address(address) => panic!("expected name, got: {}", address),
+        }
+    }
+
+    fn bootstrap_address(peer: &str) -> SocketAddr {
+        match parse_bootstrap_peer(peer).unwrap() {
+            BootstrapPeer::Address(address) => address,
+            BootstrapPeer::Name(host, port) => panic!("expected address, got: {}:{}", host, port),
+        }
+    }
+
+    #[test]
+    fn test_parse_bootstrap_peer() {
+        assert_eq!(
+            bootstrap_name("boot.tzbeta.net:9733"),
+            ("boot.tzbeta.net".to_owned(), 9733)
+        );
+        assert_eq!(
+            bootstrap_name("boot.tzbeta.net"),
+            ("boot.tzbeta.net".to_owned(), DEFAULT_BOOTSTRAP_PEER_PORT)
+        );
+        assert_eq!(
+            bootstrap_address("10.0.0.1:9733"),
+            ([10, 0, 0, 1], 9733).into()
+        );
+        assert_eq!(
+            bootstrap_address("10.0.0.1"),
+            ([10, 0, 0, 1], DEFAULT_BOOTSTRAP_PEER_PORT).into()
+        );
+        assert_eq!(
+            bootstrap_address("[::1]:9733"),
+            "[::1]:9733".parse::<SocketAddr>().unwrap()
+        );
+        assert_eq!(
+            bootstrap_address("::1"),
+            SocketAddr::new("::1".parse().unwrap(), DEFAULT_BOOTSTRAP_PEER_PORT)
+        );
+
+        for invalid in &[
+            "boot.tzbeta.net:port",
+            "boot.tzbeta.net:99999",
+            "[::1]:port",
+        ] {
+            assert!(
+                matches!(
+                    parse_bootstrap_peer(invalid),
+                    Err(ConfigLoadError::InvalidValue { .. })
+                ),
+                "{} should be invalid",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_trusted_peer() {
+        let peer = parse_trusted_peer("10.0.0.1:9732").unwrap();
+        assert_eq!(peer.address, ([10, 0, 0, 1], 9732).into());
+        assert!(peer.peer_id.is_none());
+
+        let peer = parse_trusted_peer("[::1]:9732").unwrap();
+        assert_eq!(peer.address, "[::1]:9732".parse::<SocketAddr>().unwrap());
+
+        let peer_id = CryptoboxPublicKeyHash::try_from(vec![1; 16]).unwrap();
+        let peer =
+            parse_trusted_peer(&format!("{}@10.0.0.1:9732", peer_id.to_base58_check())).unwrap();
+        assert_eq!(peer.address, ([10, 0, 0, 1], 9732).into());
+        assert_eq!(peer.peer_id, Some(peer_id));
+
+        for invalid in &[
+            "10.0.0.1",
+            "::1",
+            "boot.tzbeta.net:9732",
+            "10.0.0.1:port",
+            "invalid@10.0.0.1:9732",
+        ] {
+            assert!(
+                matches!(
+                    parse_trusted_peer(invalid),
+                    Err(ConfigLoadError::InvalidValue { .. })
+                ),
+                "{} should be invalid",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_into_config_args_override_file() {
+        let identity_file =
+            std::env::temp_dir().join(format!("tezedge_redux_cli_{}.json", std::process::id()));
+        let mut config_file: ConfigFile = toml::from_str(&format!(
+            r#"
+            network = "granadanet"
+            p2p_address = "127.0.0.1:19732"
+            identity_file = "{}"
+            pow_target = 0.0
+            peers_connected_max = 5
+            peers_incoming_max = 3
+            bootstrap_peers = ["boot.tzbeta.net"]
+            "#,
+            identity_file.display()
+        ))
+        .unwrap();
+        let args = app().get_matches_from(vec![
+            "tezedge_redux",
+            "--peers-connected-max",
+            "7",
+            "--bootstrap-peers",
+            "10.0.0.1:9732,other.tzbeta.net",
+            "--private-node",
+        ]);
+        config_file.merge_args(&args).unwrap();
+        let config = config_file.into_config();
+        let _ = fs::remove_file(&identity_file);
+        let config = config.unwrap();
+        let defaults = default_config();
+
+        // only in config file.
+        assert_eq!(config.chain_id, TezosNetwork::Granadanet.chain_id());
+        assert_eq!(config.p2p_address, ([127, 0, 0, 1], 19732).into());
+        assert_eq!(config.peers_incoming_max, 3);
+        // in both, command line argument wins.
+        assert_eq!(config.peers_connected_max, 7);
+        assert_eq!(
+            config.bootstrap_peers,
+            vec![("other.tzbeta.net".to_owned(), DEFAULT_BOOTSTRAP_PEER_PORT)]
+        );
+        assert_eq!(
+            config.bootstrap_addresses,
+            vec![([10, 0, 0, 1], 9732).into()]
+        );
+        // only in command line arguments.
+        assert!(config.private_node);
+        // in neither, default is used.
+        assert_eq!(config.peers_potential_max, defaults.peers_potential_max);
+        assert_eq!(config.rpc_address, defaults.rpc_address);
+    }
+}
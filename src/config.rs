@@ -1,10 +1,16 @@
 use hex::FromHex;
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 
+use crate::peer::handshaking::PeerHandshakingStage;
+use crate::peer::score::PeerScore;
 use crate::shell_compatibility_version::ShellCompatibilityVersion;
 use crypto::{
     crypto_box::{CryptoKey, PublicKey, SecretKey},
-    hash::{CryptoboxPublicKeyHash, HashTrait},
+    hash::{BlockHash, ChainId, CryptoboxPublicKeyHash, HashTrait},
     proof_of_work::ProofOfWork,
 };
 use tezos_identity::Identity;
@@ -32,46 +38,266 @@ pub fn identity_1() -> Identity {
     Identity::from_json(IDENTITY).unwrap()
 }
 
+/// Network preset, which determines chain name, supported versions
+/// and default bootstrap peers.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TezosNetwork {
+    Mainnet,
+    Granadanet,
+}
+
+impl TezosNetwork {
+    pub fn shell_compatibility_version(&self) -> ShellCompatibilityVersion {
+        match self {
+            Self::Mainnet => {
+                ShellCompatibilityVersion::new("TEZOS_MAINNET".to_owned(), vec![0, 1], vec![1])
+            }
+            Self::Granadanet => ShellCompatibilityVersion::new(
+                "TEZOS_GRANADANET_2021-05-21T15:00:00Z".to_owned(),
+                vec![0],
+                vec![1],
+            ),
+        }
+    }
+
+    pub fn chain_id(&self) -> ChainId {
+        let chain_id = match self {
+            Self::Mainnet => "NetXdQprcVkpaWU",
+            Self::Granadanet => "NetXz969SFaFn8k",
+        };
+        ChainId::from_base58_check(chain_id).unwrap()
+    }
+
+    pub fn genesis_block_hash(&self) -> BlockHash {
+        let hash = match self {
+            Self::Mainnet => "BLockGenesisGenesisGenesisGenesisGenesisf79b5d1CoW2",
+            Self::Granadanet => "BLockGenesisGenesisGenesisGenesisGenesis7e8c4d4snJW",
+        };
+        BlockHash::from_base58_check(hash).unwrap()
+    }
+
+    /// Default bootstrap peers (dns names) for the network.
+    pub fn bootstrap_peers(&self) -> Vec<(String, Port)> {
+        match self {
+            Self::Mainnet => vec![("boot.tzbeta.net".to_owned(), 9732)],
+            Self::Granadanet => vec![("granadanet.teztnets.xyz".to_owned(), 9732)],
+        }
+    }
+}
+
+impl FromStr for TezosNetwork {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mainnet" => Ok(Self::Mainnet),
+            "granadanet" => Ok(Self::Granadanet),
+            _ => Err(format!("unknown network: {}", s)),
+        }
+    }
+}
+
+/// Peer which we always keep connected, regardless of peer thresholds
+/// and graylist.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrustedPeer {
+    pub address: SocketAddr,
+    /// If set, peer at `address` is only trusted if it has this identity.
+    pub peer_id: Option<CryptoboxPublicKeyHash>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Config {
-    pub port: Port,
+    /// Address to listen on for incoming p2p connections. Its port is
+    /// also the one we advertise to peers.
+    pub p2p_address: SocketAddr,
+    pub rpc_address: SocketAddr,
+    /// Dns names (with ports) of the peers used for bootstrapping.
+    pub bootstrap_peers: Vec<(String, Port)>,
+    /// Static addresses of the peers used for bootstrapping.
+    pub bootstrap_addresses: Vec<SocketAddr>,
+    pub trusted_peers: Vec<TrustedPeer>,
+    /// Directory where the database is stored.
+    pub data_dir: PathBuf,
     pub disable_mempool: bool,
+    /// In private mode we only connect to and accept connections from
+    /// `trusted_peers`, and we don't take part in the peer exchange.
     pub private_node: bool,
     pub pow_target: f64,
+    /// Seed for [crate::service::RandomnessServiceDefault]. If not set,
+    /// randomness is seeded by the OS and runs can't be reproduced.
+    pub randomness_seed: Option<u64>,
     pub identity: Identity,
     pub shell_compatibility_version: ShellCompatibilityVersion,
+    /// Id of the chain we are following.
+    pub chain_id: ChainId,
+    /// Hash of the chain's genesis block. We report it as our current
+    /// head, until we download any block headers.
+    pub genesis_block_hash: BlockHash,
+
+    /// Max time peer can be in connecting stage before it's disconnected.
+    pub peer_connecting_timeout: Duration,
+    /// Max time each of the handshake's message exchanges can take
+    /// before peer is disconnected.
+    pub peer_handshaking_connection_message_timeout: Duration,
+    pub peer_handshaking_metadata_message_timeout: Duration,
+    pub peer_handshaking_ack_message_timeout: Duration,
+    /// Delay before reconnecting to the peer after the failed outgoing
+    /// connection attempt. Doubles with each consecutive failure.
+    pub peer_connection_backoff: Duration,
+    /// Max number of consecutive failed outgoing connection attempts,
+    /// after which peer's address is forgotten.
+    pub peer_connection_attempts_max: u32,
+    /// Peer is disconnected once its score falls to this threshold.
+    pub peer_score_disconnect_threshold: PeerScore,
+    /// Peer is graylisted once its score falls to this threshold.
+    pub peer_score_graylist_threshold: PeerScore,
+
+    /// Low threshold for potential peers. If number of potential peers
+    /// falls below it, bootstrap peers are resolved/added again.
+    pub peers_potential_min: usize,
+    /// Max number of potential peers we keep in state.
+    pub peers_potential_max: usize,
+    /// Low threshold for connected peers. If number of connected peers
+    /// falls below it, we will try to connect to more peers.
+    pub peers_connected_min: usize,
+    /// High threshold for connected peers. When reached, we stop
+    /// connecting to new peers and reject incoming connections.
+    pub peers_connected_max: usize,
+    /// Max number of connected peers, which connected to us. Counts
+    /// towards `peers_connected_max` as well.
+    pub peers_incoming_max: usize,
+    /// Max number of connections, which are still in connecting or
+    /// handshaking stage.
+    pub peers_pending_max: usize,
+
+    /// For how long peer's address stays graylisted.
+    pub peers_graylist_timeout: Duration,
+    /// Interval for persisting connected peers.
+    pub peers_put_interval: Duration,
+    /// Min interval between `Bootstrap`/`Advertise` messages exchanged
+    /// with the same peer, in each direction.
+    pub peers_advertise_interval: Duration,
+
+    /// Interval after which successfully resolved bootstrap dns names
+    /// are resolved again.
+    pub peers_dns_lookup_refresh_interval: Duration,
+    /// Delay before the first retry of the failed dns lookup. It doubles
+    /// with each consecutive failure, up to `peers_dns_lookup_retry_backoff_max`.
+    ///
+    /// Also the min interval between lookups triggered by
+    /// `peers_potential_min` threshold.
+    pub peers_dns_lookup_retry_backoff: Duration,
+    pub peers_dns_lookup_retry_backoff_max: Duration,
+    /// Pending dns lookup fails with timeout error after this long.
+    pub peers_dns_lookup_timeout: Duration,
+
+    /// Max time to wait for the block headers requested from the peer.
+    pub chain_block_headers_get_timeout: Duration,
+
+    /// State snapshot is persisted after every `state_snapshot_interval`
+    /// actions. Replay starts from, and checks against, these snapshots.
+    pub state_snapshot_interval: u64,
+}
+
+impl Config {
+    pub fn peer_handshaking_timeout(&self, stage: PeerHandshakingStage) -> Duration {
+        match stage {
+            PeerHandshakingStage::ConnectionMessage => {
+                self.peer_handshaking_connection_message_timeout
+            }
+            PeerHandshakingStage::MetadataMessage => self.peer_handshaking_metadata_message_timeout,
+            PeerHandshakingStage::AckMessage => self.peer_handshaking_ack_message_timeout,
+        }
+    }
 }
 
 pub fn default_config() -> Config {
     let pow_target = 26.0;
+    let network = TezosNetwork::Mainnet;
     Config {
-        port: 9732,
+        p2p_address: ([0, 0, 0, 0], 9732).into(),
+        rpc_address: ([0, 0, 0, 0], 18732).into(),
+        bootstrap_peers: network.bootstrap_peers(),
+        bootstrap_addresses: vec![],
+        trusted_peers: vec![],
+        data_dir: PathBuf::from("./data"),
         disable_mempool: false,
         private_node: false,
         pow_target,
+        randomness_seed: None,
         // identity: Identity::generate(pow_target).unwrap(),
         identity: identity_1(),
-        shell_compatibility_version: ShellCompatibilityVersion::new(
-            "TEZOS_MAINNET".to_owned(),
-            vec![0, 1],
-            vec![1],
-        ),
+        shell_compatibility_version: network.shell_compatibility_version(),
+        chain_id: network.chain_id(),
+        genesis_block_hash: network.genesis_block_hash(),
+        peer_connecting_timeout: Duration::from_secs(8),
+        peer_handshaking_connection_message_timeout: Duration::from_secs(4),
+        peer_handshaking_metadata_message_timeout: Duration::from_secs(4),
+        peer_handshaking_ack_message_timeout: Duration::from_secs(4),
+        peer_connection_backoff: Duration::from_secs(10),
+        peer_connection_attempts_max: 6,
+        peer_score_disconnect_threshold: -50,
+        peer_score_graylist_threshold: -100,
+        peers_potential_min: 20,
+        peers_potential_max: 80,
+        peers_connected_min: 10,
+        peers_connected_max: 40,
+        peers_incoming_max: 30,
+        peers_pending_max: 10,
+        peers_graylist_timeout: Duration::from_secs(15 * 60),
+        peers_put_interval: Duration::from_secs(60),
+        peers_advertise_interval: Duration::from_secs(60),
+        peers_dns_lookup_refresh_interval: Duration::from_secs(10 * 60),
+        peers_dns_lookup_retry_backoff: Duration::from_secs(5),
+        peers_dns_lookup_retry_backoff_max: Duration::from_secs(5 * 60),
+        peers_dns_lookup_timeout: Duration::from_secs(30),
+        chain_block_headers_get_timeout: Duration::from_secs(10),
+        state_snapshot_interval: 10000,
     }
 }
 
 pub fn test_config() -> Config {
     let pow_target = 0.0;
     Config {
-        port: 19732,
+        p2p_address: ([127, 0, 0, 1], 19732).into(),
+        rpc_address: ([127, 0, 0, 1], 18733).into(),
+        bootstrap_peers: vec![],
+        bootstrap_addresses: vec![],
+        trusted_peers: vec![],
+        data_dir: PathBuf::from("./data-test"),
         disable_mempool: false,
         private_node: false,
         pow_target,
+        randomness_seed: Some(0),
         // identity: Identity::generate(pow_target).unwrap(),
         identity: identity_1(),
-        shell_compatibility_version: ShellCompatibilityVersion::new(
-            "TEZOS_GRANADANET_2021-05-21T15:00:00Z".to_owned(),
-            vec![0],
-            vec![1],
-        ),
+        shell_compatibility_version: TezosNetwork::Granadanet.shell_compatibility_version(),
+        chain_id: TezosNetwork::Granadanet.chain_id(),
+        genesis_block_hash: TezosNetwork::Granadanet.genesis_block_hash(),
+        peer_connecting_timeout: Duration::from_secs(8),
+        peer_handshaking_connection_message_timeout: Duration::from_secs(4),
+        peer_handshaking_metadata_message_timeout: Duration::from_secs(4),
+        peer_handshaking_ack_message_timeout: Duration::from_secs(4),
+        peer_connection_backoff: Duration::from_secs(10),
+        peer_connection_attempts_max: 6,
+        peer_score_disconnect_threshold: -50,
+        peer_score_graylist_threshold: -100,
+        peers_potential_min: 1,
+        peers_potential_max: 80,
+        peers_connected_min: 1,
+        peers_connected_max: 10,
+        peers_incoming_max: 10,
+        peers_pending_max: 10,
+        peers_graylist_timeout: Duration::from_secs(15 * 60),
+        peers_put_interval: Duration::from_secs(60),
+        peers_advertise_interval: Duration::from_secs(60),
+        peers_dns_lookup_refresh_interval: Duration::from_secs(10 * 60),
+        peers_dns_lookup_retry_backoff: Duration::from_secs(5),
+        peers_dns_lookup_retry_backoff_max: Duration::from_secs(5 * 60),
+        peers_dns_lookup_timeout: Duration::from_secs(30),
+        chain_block_headers_get_timeout: Duration::from_secs(10),
+        state_snapshot_interval: 10000,
     }
 }
@@ -14,12 +14,22 @@ use crate::peer::chunk::write::peer_chunk_write_effects::peer_chunk_write_effect
 use crate::peer::connection::outgoing::peer_connection_outgoing_effects;
 use crate::peer::disconnection::peer_disconnection_effects;
 use crate::peer::handshaking::peer_handshaking_effects;
+use crate::peer::message::read::peer_message_read_effects;
+use crate::peer::message::write::peer_message_write_effects;
 use crate::peer::peer_effects;
+use crate::peer::score::peer_score_effects;
 
 use crate::peers::add::multi::peers_add_multi_effects;
+use crate::peers::advertise::peers_advertise_effects;
 use crate::peers::dns_lookup::peers_dns_lookup_effects;
+use crate::peers::graylist::peers_graylist_effects;
+use crate::peers::timeouts::peers_timeouts_effects;
+
+use crate::chain::block_headers::chain_block_headers_effects;
+use crate::chain::current_head::chain_current_head_effects;
 
 use crate::storage::block_header::put::storage_block_header_put_effects;
+use crate::storage::peers::storage_peers_effects;
 use crate::storage::request::storage_request_effects;
 use crate::storage::state_snapshot::create::{
     storage_state_snapshot_create_effects, StorageStateSnapshotCreateAction,
@@ -27,6 +37,8 @@ use crate::storage::state_snapshot::create::{
 
 use crate::rpc::rpc_effects;
 
+use crate::tick::tick_effects;
+
 fn log_effects<S: Service>(_store: &mut Store<State, S, Action>, action: &ActionWithId<Action>) {
     eprintln!("[+] Action: {:#?}", &action);
     // eprintln!("[+] State: {:#?}\n", store.state());
@@ -37,7 +49,7 @@ fn last_action_effects<S: Service>(
     action: &ActionWithId<Action>,
 ) {
     let last_action_id_num: u64 = store.state().last_action_id.into();
-    if last_action_id_num % 10000 == 0 {
+    if last_action_id_num % store.state().config.state_snapshot_interval == 0 {
         store.dispatch(StorageStateSnapshotCreateAction {}.into());
     }
     store.service.storage().request_send(StorageRequest {
@@ -53,20 +65,34 @@ pub fn effects<S: Service>(store: &mut Store<State, S, Action>, action: &ActionW
 
     storage_state_snapshot_create_effects(store, action);
 
+    tick_effects(store, action);
+
     peers_dns_lookup_effects(store, action);
     peers_add_multi_effects(store, action);
+    peers_timeouts_effects(store, action);
+    // must be before `peers_graylist_effects`, so that graylisted
+    // peer is stored before it's disconnected and removed.
+    storage_peers_effects(store, action);
+    peers_graylist_effects(store, action);
+    peers_advertise_effects(store, action);
 
     peer_effects(store, action);
     peer_connection_outgoing_effects(store, action);
     peer_connection_incoming_accept_effects(store, action);
     peer_connection_incoming_effects(store, action);
     peer_handshaking_effects(store, action);
+    peer_message_write_effects(store, action);
+    peer_message_read_effects(store, action);
     peer_binary_message_write_effects(store, action);
     peer_binary_message_read_effects(store, action);
     peer_chunk_write_effects(store, action);
     peer_chunk_read_effects(store, action);
+    peer_score_effects(store, action);
     peer_disconnection_effects(store, action);
 
+    chain_current_head_effects(store, action);
+    chain_block_headers_effects(store, action);
+
     storage_block_header_put_effects(store, action);
     storage_request_effects(store, action);
 
@@ -0,0 +1,167 @@
+//! Loading, validation and generation of the node's identity.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use crypto::proof_of_work::check_proof_of_work;
+use tezos_identity::{Identity, IdentityError};
+
+/// Interval at which we report that identity generation is still in progress.
+const GENERATE_PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub enum IdentityLoadError {
+    Io(io::Error),
+    Identity(IdentityError),
+    /// Identity's proof of work doesn't satisfy `pow_target`.
+    InsufficientProofOfWork {
+        pow_target: f64,
+    },
+    /// Identity generator thread exited without producing identity.
+    GeneratorFailed,
+}
+
+impl From<io::Error> for IdentityLoadError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<IdentityError> for IdentityLoadError {
+    fn from(error: IdentityError) -> Self {
+        Self::Identity(error)
+    }
+}
+
+pub fn identity_check_proof_of_work(
+    identity: &Identity,
+    pow_target: f64,
+) -> Result<(), IdentityLoadError> {
+    let pow_data = [
+        identity.public_key.as_ref(),
+        identity.proof_of_work_stamp.as_ref(),
+    ]
+    .concat();
+    check_proof_of_work(&pow_data, pow_target)
+        .map_err(|_| IdentityLoadError::InsufficientProofOfWork { pow_target })
+}
+
+/// Load identity from json file and validate its proof of work.
+pub fn identity_load(path: &Path, pow_target: f64) -> Result<Identity, IdentityLoadError> {
+    let identity = Identity::from_json(&fs::read_to_string(path)?)?;
+    identity_check_proof_of_work(&identity, pow_target)?;
+    Ok(identity)
+}
+
+pub fn identity_store(path: &Path, identity: &Identity) -> Result<(), IdentityLoadError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, identity.as_json()?)?;
+    Ok(())
+}
+
+/// Identity, which is being generated on a separate thread.
+pub struct IdentityGenerating {
+    pow_target: f64,
+    receiver: mpsc::Receiver<Result<Identity, IdentityError>>,
+}
+
+impl IdentityGenerating {
+    /// Generated identity, or `None` if generation is still in progress.
+    pub fn try_get(&self) -> Option<Result<Identity, IdentityLoadError>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result.map_err(|err| err.into())),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(Err(IdentityLoadError::GeneratorFailed)),
+        }
+    }
+
+    /// Block until identity is generated, periodically reporting that
+    /// it's still in progress.
+    pub fn wait(self) -> Result<Identity, IdentityLoadError> {
+        loop {
+            match self.receiver.recv_timeout(GENERATE_PROGRESS_INTERVAL) {
+                Ok(result) => return Ok(result?),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    eprintln!(
+                        "[+] Generating identity (pow_target: {})...",
+                        self.pow_target
+                    );
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err(IdentityLoadError::GeneratorFailed);
+                }
+            }
+        }
+    }
+}
+
+fn identity_generate_with<F>(
+    pow_target: f64,
+    generate: F,
+) -> Result<IdentityGenerating, IdentityLoadError>
+where
+    F: FnOnce() -> Result<Identity, IdentityError> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    thread::Builder::new()
+        .name("identity-generator".to_owned())
+        .spawn(move || {
+            let _ = tx.send(generate());
+        })?;
+    Ok(IdentityGenerating {
+        pow_target,
+        receiver: rx,
+    })
+}
+
+/// Start generating new identity with proof of work.
+///
+/// Generation is cpu heavy, so it's done on a separate thread and
+/// this returns right away.
+pub fn identity_generate(pow_target: f64) -> Result<IdentityGenerating, IdentityLoadError> {
+    identity_generate_with(pow_target, move || Identity::generate(pow_target))
+}
+
+/// Load identity from the file. If file doesn't exist, generate new
+/// identity and persist it at the given path.
+///
+/// Node can't start without identity, so this waits for the generation
+/// to finish.
+pub fn identity_load_or_generate(
+    path: &Path,
+    pow_target: f64,
+) -> Result<Identity, IdentityLoadError> {
+    if path.exists() {
+        return identity_load(path, pow_target);
+    }
+    let identity = identity_generate(pow_target)?.wait()?;
+    identity_store(path, &identity)?;
+    Ok(identity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_generate_doesnt_block() {
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        // generation, which runs until we let it finish.
+        let generating = identity_generate_with(0.0, move || {
+            let _ = release_rx.recv();
+            Identity::generate(0.0)
+        })
+        .unwrap();
+        assert!(generating.try_get().is_none());
+
+        release_tx.send(()).unwrap();
+        let identity = generating.wait().unwrap();
+        assert!(identity_check_proof_of_work(&identity, 0.0).is_ok());
+    }
+}
@@ -1,4 +1,5 @@
 use redux_rs::Store;
+use std::path::Path;
 
 pub mod io_error_kind;
 
@@ -8,7 +9,17 @@ use event::Event;
 pub mod action;
 
 pub mod config;
-use config::default_config;
+
+pub mod cli;
+use cli::{config_file_from_args, replay_args, ReplayArgs};
+
+pub mod identity;
+
+pub mod replay;
+use replay::{replay, ReplayResult};
+
+#[cfg(test)]
+pub mod simulator;
 
 mod state;
 pub use state::State;
@@ -26,71 +37,103 @@ pub mod shell_compatibility_version;
 pub mod peer;
 
 pub mod peers;
-use peers::dns_lookup::PeersDnsLookupInitAction;
+
+pub mod chain;
 
 pub mod storage;
-use crate::storage::block_header::put::StorageBlockHeadersPutAction;
+use crate::storage::peers::StoragePeersLoadAction;
 use crate::storage::state_snapshot::create::StorageStateSnapshotCreateAction;
 
 pub mod rpc;
 
+pub mod tick;
+use tick::{TickAction, TICK_IDLE_INTERVAL, TICK_INTERVAL};
+
 pub mod service;
 use crate::service::RpcServiceDefault;
 use service::mio_service::MioInternalEventsContainer;
 use service::{
     DnsServiceDefault, MioService, MioServiceDefault, RandomnessServiceDefault, Service,
-    ServiceDefault, StorageServiceDefault,
+    ServiceDefault, StorageServiceDefault, TimeService, TimeServiceDefault,
 };
 
 pub mod tmp;
-use tmp::persistent_storage::{gen_block_headers, init_storage};
+use tmp::persistent_storage::init_storage;
 
 pub type Port = u16;
 
+fn run_replay(data_dir: &Path, args: ReplayArgs) {
+    let (persistent_storage, _) = init_storage(data_dir);
+    let result = replay(&persistent_storage, args.from, args.to).expect("replay failed");
+    println!("{}", serde_json::to_string_pretty(&result).unwrap());
+
+    if let ReplayResult::Diverged { .. } = result {
+        std::process::exit(1);
+    }
+}
+
 fn main() {
-    let listen_address = ([0, 0, 0, 0], 9734).into();
+    let args = cli::args();
+    let config_file = config_file_from_args(&args).expect("failed to load config");
+
+    if let Some(replay_args) = replay_args(&args).expect("invalid replay arguments") {
+        return run_replay(&config_file.data_dir(), replay_args);
+    }
 
-    let persistent_storage = init_storage();
+    let config = config_file.into_config().expect("failed to load config");
 
-    let mio_service = MioServiceDefault::new(listen_address);
-    let storage_service =
-        StorageServiceDefault::init(mio_service.waker(), persistent_storage.clone());
-    let rpc_service = RpcServiceDefault::init(mio_service.waker(), persistent_storage.clone());
+    let (persistent_storage, peers_storage) = init_storage(&config.data_dir);
+
+    let mio_service = MioServiceDefault::new(config.p2p_address);
+    let storage_service = StorageServiceDefault::init(
+        mio_service.waker(),
+        persistent_storage.clone(),
+        peers_storage,
+    );
+    let rpc_service = RpcServiceDefault::init(
+        mio_service.waker(),
+        config.rpc_address,
+        config.state_snapshot_interval,
+        persistent_storage.clone(),
+    );
 
     let service = ServiceDefault {
-        randomness: RandomnessServiceDefault::default(),
-        dns: DnsServiceDefault::default(),
+        time: TimeServiceDefault::default(),
+        randomness: RandomnessServiceDefault::new(config.randomness_seed),
+        dns: DnsServiceDefault::init(mio_service.waker()),
         mio: mio_service,
         storage: storage_service,
         rpc: rpc_service,
     };
 
-    let mut store = Store::new(reducer, service, State::new(default_config()));
+    let mut store = Store::new(reducer, service, State::new(config));
 
     store.add_middleware(effects);
 
+    store
+        .service()
+        .mio()
+        .peer_connection_incoming_listen_start()
+        .expect("failed to start listening for incoming p2p connections");
+
+    let time = store.service().time().now();
+    store.dispatch(TickAction { time }.into());
+
     // Persist initial state.
     store.dispatch(StorageStateSnapshotCreateAction {}.into());
 
-    store.dispatch(
-        PeersDnsLookupInitAction {
-            address: "boot.tzbeta.net".to_owned(),
-            port: 9732,
-        }
-        .into(),
-    );
-    store.dispatch(
-        StorageBlockHeadersPutAction {
-            block_headers: gen_block_headers(),
-        }
-        .into(),
-    );
+    store.dispatch(StoragePeersLoadAction {}.into());
 
     let mut events = MioInternalEventsContainer::with_capacity(1024);
+    let mut events_since_last_tick = false;
 
     loop {
-        store.service().mio().wait_for_events(&mut events, None);
+        store
+            .service()
+            .mio()
+            .wait_for_events(&mut events, Some(TICK_INTERVAL));
         for event in events.into_iter() {
+            events_since_last_tick = true;
             match store.service().mio().transform_event(event) {
                 Event::P2pServer(p2p_server_event) => store.dispatch(p2p_server_event.into()),
                 Event::P2pPeer(p2p_peer_event) => store.dispatch(p2p_peer_event.into()),
@@ -98,5 +141,17 @@ fn main() {
                 _ => {}
             }
         }
+
+        let time = store.service().time().now();
+        let since_last_tick = time.duration_since(store.state().time).unwrap_or_default();
+        let tick_interval = if events_since_last_tick {
+            TICK_INTERVAL
+        } else {
+            TICK_IDLE_INTERVAL
+        };
+        if since_last_tick >= tick_interval {
+            store.dispatch(TickAction { time }.into());
+            events_since_last_tick = false;
+        }
     }
 }
@@ -1,7 +1,7 @@
 use redux_rs::{ActionWithId, Store};
 use tezos_messages::p2p::{
     binary_message::SizeFromChunk,
-    encoding::{ack::AckMessage, metadata::MetadataMessage},
+    encoding::{ack::AckMessage, metadata::MetadataMessage, peer::PeerMessageResponse},
 };
 
 use crate::{
@@ -12,7 +12,8 @@ use crate::{
             peer_chunk_read_state::{PeerChunkRead, PeerChunkReadState},
         },
         handshaking::{PeerHandshaking, PeerHandshakingStatus},
-        PeerStatus,
+        message::read::PeerMessageReadState,
+        PeerHandshaked, PeerStatus,
     },
     service::Service,
     State,
@@ -20,8 +21,8 @@ use crate::{
 
 use super::{
     peer_binary_message_read_actions::{
-        PeerBinaryMessageReadErrorAction, PeerBinaryMessageReadReadyAction,
-        PeerBinaryMessageReadSizeReadyAction,
+        PeerBinaryMessageReadChunkReadyAction, PeerBinaryMessageReadErrorAction,
+        PeerBinaryMessageReadReadyAction, PeerBinaryMessageReadSizeReadyAction,
     },
     peer_binary_message_read_state::PeerBinaryMessageReadState,
 };
@@ -35,7 +36,7 @@ pub fn peer_binary_message_read_effects<S>(
     match &action.action {
         Action::PeerBinaryMessageReadInit(action) => {
             if let Some(peer) = store.state.get().peers.get(&action.address) {
-                match &peer.status {
+                let binary_message_state = match &peer.status {
                     PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
                         PeerHandshakingStatus::MetadataMessageReadPending {
                             binary_message_state,
@@ -44,114 +45,110 @@ pub fn peer_binary_message_read_effects<S>(
                         | PeerHandshakingStatus::AckMessageReadPending {
                             binary_message_state,
                             ..
-                        } => match binary_message_state {
-                            PeerBinaryMessageReadState::PendingFirstChunk { .. } => store.dispatch(
-                                PeerChunkReadInitAction {
-                                    address: action.address,
-                                }
-                                .into(),
-                            ),
-                            _ => {}
-                        },
-                        _ => {}
+                        } => binary_message_state,
+                        _ => return,
                     },
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_read:
+                            PeerMessageReadState::Pending {
+                                binary_message_state,
+                            },
+                        ..
+                    }) => binary_message_state,
+                    _ => return,
+                };
+
+                match binary_message_state {
+                    PeerBinaryMessageReadState::PendingFirstChunk { .. } => store.dispatch(
+                        PeerChunkReadInitAction {
+                            address: action.address,
+                        }
+                        .into(),
+                    ),
                     _ => {}
                 }
             }
         }
         Action::PeerChunkReadReady(action) => {
             if let Some(peer) = store.state.get().peers.get(&action.address) {
-                match &peer.status {
-                    PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
-                        PeerHandshakingStatus::MetadataMessageReadPending {
-                            binary_message_state:
-                                PeerBinaryMessageReadState::PendingFirstChunk {
-                                    chunk:
-                                        PeerChunkRead {
-                                            state: PeerChunkReadState::Ready { chunk },
-                                            ..
-                                        },
-                                    ..
-                                },
-                            ..
-                        } => match MetadataMessage::size_from_chunk(&chunk) {
-                            Ok(size) => store.dispatch(
-                                PeerBinaryMessageReadSizeReadyAction {
-                                    address: action.address,
-                                    size,
-                                }
-                                .into(),
-                            ),
-                            Err(err) => store.dispatch(
-                                PeerBinaryMessageReadErrorAction {
-                                    address: action.address,
-                                    error: err.into(),
-                                }
-                                .into(),
-                            ),
+                let (binary_message_state, size_from_chunk): (_, fn(&[u8]) -> _) =
+                    match &peer.status {
+                        PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
+                            PeerHandshakingStatus::MetadataMessageReadPending {
+                                binary_message_state,
+                                ..
+                            } => (binary_message_state, |chunk| {
+                                MetadataMessage::size_from_chunk(chunk)
+                            }),
+                            PeerHandshakingStatus::AckMessageReadPending {
+                                binary_message_state,
+                                ..
+                            } => (binary_message_state, |chunk| {
+                                AckMessage::size_from_chunk(chunk)
+                            }),
+                            _ => return,
                         },
-                        PeerHandshakingStatus::AckMessageReadPending {
-                            binary_message_state:
-                                PeerBinaryMessageReadState::PendingFirstChunk {
-                                    chunk:
-                                        PeerChunkRead {
-                                            state: PeerChunkReadState::Ready { chunk },
-                                            ..
-                                        },
-                                    ..
+                        PeerStatus::Handshaked(PeerHandshaked {
+                            message_read:
+                                PeerMessageReadState::Pending {
+                                    binary_message_state,
                                 },
                             ..
-                        } => match AckMessage::size_from_chunk(&chunk) {
-                            Ok(size) => store.dispatch(
-                                PeerBinaryMessageReadSizeReadyAction {
-                                    address: action.address,
-                                    size,
-                                }
-                                .into(),
-                            ),
-                            Err(err) => store.dispatch(
-                                PeerBinaryMessageReadErrorAction {
-                                    address: action.address,
-                                    error: err.into(),
-                                }
-                                .into(),
-                            ),
-                        },
-                        PeerHandshakingStatus::MetadataMessageReadPending {
-                            binary_message_state,
-                            ..
-                        }
-                        | PeerHandshakingStatus::AckMessageReadPending {
-                            binary_message_state,
-                            ..
-                        } => match binary_message_state {
-                            PeerBinaryMessageReadState::Pending { .. } => store.dispatch(
-                                PeerChunkReadInitAction {
-                                    address: action.address,
-                                }
-                                .into(),
-                            ),
-                            PeerBinaryMessageReadState::Ready { message, .. } => {
-                                let message = message.clone();
-                                store.dispatch(
-                                    PeerBinaryMessageReadReadyAction {
-                                        address: action.address,
-                                        message,
-                                    }
-                                    .into(),
-                                )
+                        }) => (binary_message_state, |chunk| {
+                            PeerMessageResponse::size_from_chunk(chunk)
+                        }),
+                        _ => return,
+                    };
+
+                match binary_message_state {
+                    PeerBinaryMessageReadState::PendingFirstChunk {
+                        chunk:
+                            PeerChunkRead {
+                                state: PeerChunkReadState::Ready { chunk },
+                                ..
+                            },
+                    } => match size_from_chunk(&chunk) {
+                        Ok(size) => store.dispatch(
+                            PeerBinaryMessageReadSizeReadyAction {
+                                address: action.address,
+                                size,
                             }
-                            _ => {}
-                        },
-                        _ => {}
+                            .into(),
+                        ),
+                        Err(err) => store.dispatch(
+                            PeerBinaryMessageReadErrorAction {
+                                address: action.address,
+                                error: err.into(),
+                            }
+                            .into(),
+                        ),
                     },
+                    PeerBinaryMessageReadState::Pending {
+                        chunk:
+                            PeerChunkRead {
+                                state: PeerChunkReadState::Ready { .. },
+                                ..
+                            },
+                        ..
+                    } => store.dispatch(
+                        PeerBinaryMessageReadChunkReadyAction {
+                            address: action.address,
+                        }
+                        .into(),
+                    ),
                     _ => {}
                 }
             }
         }
-        Action::PeerBinaryMessageReadSizeReady(action) => {
-            if let Some(peer) = store.state.get().peers.get(&action.address) {
-                match &peer.status {
+        Action::PeerBinaryMessageReadSizeReady(PeerBinaryMessageReadSizeReadyAction {
+            address,
+            ..
+        })
+        | Action::PeerBinaryMessageReadChunkReady(PeerBinaryMessageReadChunkReadyAction {
+            address,
+        }) => {
+            if let Some(peer) = store.state.get().peers.get(address) {
+                let binary_message_state = match &peer.status {
                     PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
                         PeerHandshakingStatus::MetadataMessageReadPending {
                             binary_message_state,
@@ -160,27 +157,33 @@ pub fn peer_binary_message_read_effects<S>(
                         | PeerHandshakingStatus::AckMessageReadPending {
                             binary_message_state,
                             ..
-                        } => match binary_message_state {
-                            PeerBinaryMessageReadState::Pending { .. } => store.dispatch(
-                                PeerChunkReadInitAction {
-                                    address: action.address,
-                                }
-                                .into(),
-                            ),
-                            PeerBinaryMessageReadState::Ready { message, .. } => {
-                                let message = message.clone();
-                                store.dispatch(
-                                    PeerBinaryMessageReadReadyAction {
-                                        address: action.address,
-                                        message,
-                                    }
-                                    .into(),
-                                )
-                            }
-                            _ => {}
-                        },
-                        _ => {}
+                        } => binary_message_state,
+                        _ => return,
                     },
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_read:
+                            PeerMessageReadState::Pending {
+                                binary_message_state,
+                            },
+                        ..
+                    }) => binary_message_state,
+                    _ => return,
+                };
+
+                match binary_message_state {
+                    PeerBinaryMessageReadState::Pending { .. } => {
+                        store.dispatch(PeerChunkReadInitAction { address: *address }.into())
+                    }
+                    PeerBinaryMessageReadState::Ready { message, .. } => {
+                        let message = message.clone();
+                        store.dispatch(
+                            PeerBinaryMessageReadReadyAction {
+                                address: *address,
+                                message,
+                            }
+                            .into(),
+                        )
+                    }
                     _ => {}
                 }
             }
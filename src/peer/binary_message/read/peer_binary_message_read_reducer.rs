@@ -5,7 +5,8 @@ use crate::{
     peer::{
         chunk::read::peer_chunk_read_state::{PeerChunkRead, PeerChunkReadState},
         handshaking::{PeerHandshaking, PeerHandshakingStatus},
-        PeerStatus,
+        message::read::PeerMessageReadState,
+        PeerHandshaked, PeerStatus,
     },
     State,
 };
@@ -16,7 +17,7 @@ pub fn peer_binary_message_read_reducer(state: &mut State, action: &ActionWithId
     match &action.action {
         Action::PeerBinaryMessageReadInit(action) => {
             if let Some(peer) = state.peers.get_mut(&action.address) {
-                match &mut peer.status {
+                let binary_message_state = match &mut peer.status {
                     PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
                         PeerHandshakingStatus::MetadataMessageReadPending {
                             binary_message_state,
@@ -25,27 +26,35 @@ pub fn peer_binary_message_read_reducer(state: &mut State, action: &ActionWithId
                         | PeerHandshakingStatus::AckMessageReadPending {
                             binary_message_state,
                             ..
-                        } => match binary_message_state {
-                            PeerBinaryMessageReadState::Init { crypto } => {
-                                *binary_message_state =
-                                    PeerBinaryMessageReadState::PendingFirstChunk {
-                                        chunk: PeerChunkRead {
-                                            crypto: crypto.clone(),
-                                            state: PeerChunkReadState::Init,
-                                        },
-                                    }
-                            }
-                            _ => {}
-                        },
-                        _ => {}
+                        } => binary_message_state,
+                        _ => return,
                     },
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_read:
+                            PeerMessageReadState::Pending {
+                                binary_message_state,
+                            },
+                        ..
+                    }) => binary_message_state,
+                    _ => return,
+                };
+
+                match binary_message_state {
+                    PeerBinaryMessageReadState::Init { crypto } => {
+                        *binary_message_state = PeerBinaryMessageReadState::PendingFirstChunk {
+                            chunk: PeerChunkRead {
+                                crypto: crypto.clone(),
+                                state: PeerChunkReadState::Init,
+                            },
+                        }
+                    }
                     _ => {}
                 }
             }
         }
         Action::PeerBinaryMessageReadSizeReady(action) => {
             if let Some(peer) = state.peers.get_mut(&action.address) {
-                match &mut peer.status {
+                let binary_message_state = match &mut peer.status {
                     PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
                         PeerHandshakingStatus::MetadataMessageReadPending {
                             binary_message_state,
@@ -54,41 +63,52 @@ pub fn peer_binary_message_read_reducer(state: &mut State, action: &ActionWithId
                         | PeerHandshakingStatus::AckMessageReadPending {
                             binary_message_state,
                             ..
-                        } => match binary_message_state {
-                            PeerBinaryMessageReadState::PendingFirstChunk {
-                                chunk:
-                                    PeerChunkRead {
-                                        crypto,
-                                        state: PeerChunkReadState::Ready { chunk },
-                                    },
-                            } => {
-                                if action.size < chunk.len() {
-                                    *binary_message_state = PeerBinaryMessageReadState::Pending {
-                                        buffer: Vec::new(),
-                                        size: action.size,
-                                        chunk: PeerChunkRead {
-                                            crypto: crypto.clone(),
-                                            state: PeerChunkReadState::Init,
-                                        },
-                                    };
-                                } else {
-                                    *binary_message_state = PeerBinaryMessageReadState::Ready {
-                                        crypto: crypto.clone(),
-                                        message: chunk.clone(),
-                                    };
-                                }
-                            }
-                            _ => {}
-                        },
-                        _ => {}
+                        } => binary_message_state,
+                        _ => return,
                     },
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_read:
+                            PeerMessageReadState::Pending {
+                                binary_message_state,
+                            },
+                        ..
+                    }) => binary_message_state,
+                    _ => return,
+                };
+
+                match binary_message_state {
+                    PeerBinaryMessageReadState::PendingFirstChunk {
+                        chunk:
+                            PeerChunkRead {
+                                crypto,
+                                state: PeerChunkReadState::Ready { chunk },
+                            },
+                    } => {
+                        if action.size > chunk.len() {
+                            // Message is split into multiple chunks, so
+                            // keep first chunk and wait for the rest.
+                            *binary_message_state = PeerBinaryMessageReadState::Pending {
+                                buffer: chunk.clone(),
+                                size: action.size,
+                                chunk: PeerChunkRead {
+                                    crypto: crypto.clone(),
+                                    state: PeerChunkReadState::Init,
+                                },
+                            };
+                        } else {
+                            *binary_message_state = PeerBinaryMessageReadState::Ready {
+                                crypto: crypto.clone(),
+                                message: chunk.clone(),
+                            };
+                        }
+                    }
                     _ => {}
                 }
             }
         }
         Action::PeerBinaryMessageReadChunkReady(action) => {
             if let Some(peer) = state.peers.get_mut(&action.address) {
-                match &mut peer.status {
+                let binary_message_state = match &mut peer.status {
                     PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
                         PeerHandshakingStatus::MetadataMessageReadPending {
                             binary_message_state,
@@ -97,30 +117,48 @@ pub fn peer_binary_message_read_reducer(state: &mut State, action: &ActionWithId
                         | PeerHandshakingStatus::AckMessageReadPending {
                             binary_message_state,
                             ..
-                        } => match binary_message_state {
-                            PeerBinaryMessageReadState::Pending {
-                                buffer,
-                                size,
-                                chunk:
-                                    PeerChunkRead {
-                                        crypto,
-                                        state: PeerChunkReadState::Ready { chunk },
+                        } => binary_message_state,
+                        _ => return,
+                    },
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_read:
+                            PeerMessageReadState::Pending {
+                                binary_message_state,
+                            },
+                        ..
+                    }) => binary_message_state,
+                    _ => return,
+                };
+
+                match binary_message_state {
+                    PeerBinaryMessageReadState::Pending {
+                        buffer,
+                        size,
+                        chunk:
+                            PeerChunkRead {
+                                crypto,
+                                state: PeerChunkReadState::Ready { chunk },
+                            },
+                    } => {
+                        if buffer.len() + chunk.len() <= *size {
+                            buffer.extend_from_slice(&chunk);
+                            if buffer.len() == *size {
+                                *binary_message_state = PeerBinaryMessageReadState::Ready {
+                                    crypto: crypto.clone(),
+                                    message: buffer.clone(),
+                                }
+                            } else {
+                                *binary_message_state = PeerBinaryMessageReadState::Pending {
+                                    buffer: buffer.clone(),
+                                    size: *size,
+                                    chunk: PeerChunkRead {
+                                        crypto: crypto.clone(),
+                                        state: PeerChunkReadState::Init,
                                     },
-                            } => {
-                                if buffer.len() + chunk.len() <= *size {
-                                    buffer.extend_from_slice(&chunk);
-                                    if buffer.len() == *size {
-                                        *binary_message_state = PeerBinaryMessageReadState::Ready {
-                                            crypto: crypto.clone(),
-                                            message: buffer.clone(),
-                                        }
-                                    }
                                 }
                             }
-                            _ => {}
-                        },
-                        _ => {}
-                    },
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -8,7 +8,8 @@ use crate::{
             PeerChunkWriteSetContentAction,
         },
         handshaking::{PeerHandshaking, PeerHandshakingStatus},
-        PeerStatus,
+        message::write::PeerMessageWriteState,
+        PeerHandshaked, PeerStatus,
     },
     service::Service,
     State,
@@ -30,7 +31,7 @@ pub fn peer_binary_message_write_effects<S>(
     match &action.action {
         Action::PeerBinaryMessageWriteSetContent(action) => {
             if let Some(peer) = store.state.get().peers.get(&action.address) {
-                match &peer.status {
+                let binary_message_state = match &peer.status {
                     PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
                         PeerHandshakingStatus::MetadataMessageWritePending {
                             binary_message_state,
@@ -39,36 +40,46 @@ pub fn peer_binary_message_write_effects<S>(
                         | PeerHandshakingStatus::AckMessageWritePending {
                             binary_message_state,
                             ..
-                        } => match binary_message_state {
-                            PeerBinaryMessageWriteState::Pending {
-                                chunk:
-                                    PeerChunkWrite {
-                                        state: PeerChunkWriteState::Init,
-                                        ..
-                                    },
-                                chunk_content,
+                        } => binary_message_state,
+                        _ => return,
+                    },
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_write:
+                            PeerMessageWriteState {
+                                current: binary_message_state,
+                                ..
+                            },
+                        ..
+                    }) => binary_message_state,
+                    _ => return,
+                };
+
+                match binary_message_state {
+                    PeerBinaryMessageWriteState::Pending {
+                        chunk:
+                            PeerChunkWrite {
+                                state: PeerChunkWriteState::Init,
                                 ..
-                            } => {
-                                let content = chunk_content.clone();
-                                store.dispatch(
-                                    PeerChunkWriteSetContentAction {
-                                        address: action.address,
-                                        content,
-                                    }
-                                    .into(),
-                                )
+                            },
+                        chunk_content,
+                        ..
+                    } => {
+                        let content = chunk_content.clone();
+                        store.dispatch(
+                            PeerChunkWriteSetContentAction {
+                                address: action.address,
+                                content,
                             }
-                            _ => {}
-                        },
-                        _ => {}
-                    },
+                            .into(),
+                        )
+                    }
                     _ => {}
                 }
             }
         }
         Action::PeerChunkWriteReady(action) => {
             if let Some(peer) = store.state.get().peers.get(&action.address) {
-                match &peer.status {
+                let binary_message_state = match &peer.status {
                     PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
                         PeerHandshakingStatus::MetadataMessageWritePending {
                             binary_message_state,
@@ -77,37 +88,47 @@ pub fn peer_binary_message_write_effects<S>(
                         | PeerHandshakingStatus::AckMessageWritePending {
                             binary_message_state,
                             ..
-                        } => match binary_message_state {
-                            PeerBinaryMessageWriteState::Pending {
-                                chunk:
-                                    PeerChunkWrite {
-                                        state: PeerChunkWriteState::Ready { .. },
-                                        ..
-                                    },
-                                ..
-                            } => store.dispatch(
-                                PeerBinaryMessageWriteNextChunkAction {
-                                    address: action.address,
-                                }
-                                .into(),
-                            ),
-                            PeerBinaryMessageWriteState::Ready { .. } => store.dispatch(
-                                PeerBinaryMessageWriteReadyAction {
-                                    address: action.address,
-                                }
-                                .into(),
-                            ),
-                            _ => {}
-                        },
-                        _ => {}
+                        } => binary_message_state,
+                        _ => return,
                     },
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_write:
+                            PeerMessageWriteState {
+                                current: binary_message_state,
+                                ..
+                            },
+                        ..
+                    }) => binary_message_state,
+                    _ => return,
+                };
+
+                match binary_message_state {
+                    PeerBinaryMessageWriteState::Pending {
+                        chunk:
+                            PeerChunkWrite {
+                                state: PeerChunkWriteState::Ready { .. },
+                                ..
+                            },
+                        ..
+                    } => store.dispatch(
+                        PeerBinaryMessageWriteNextChunkAction {
+                            address: action.address,
+                        }
+                        .into(),
+                    ),
+                    PeerBinaryMessageWriteState::Ready { .. } => store.dispatch(
+                        PeerBinaryMessageWriteReadyAction {
+                            address: action.address,
+                        }
+                        .into(),
+                    ),
                     _ => {}
                 }
             }
         }
         Action::PeerBinaryMessageWriteNextChunk(action) => {
             if let Some(peer) = store.state.get().peers.get(&action.address) {
-                match &peer.status {
+                let binary_message_state = match &peer.status {
                     PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
                         PeerHandshakingStatus::MetadataMessageWritePending {
                             binary_message_state,
@@ -116,27 +137,37 @@ pub fn peer_binary_message_write_effects<S>(
                         | PeerHandshakingStatus::AckMessageWritePending {
                             binary_message_state,
                             ..
-                        } => match binary_message_state {
-                            PeerBinaryMessageWriteState::Pending { chunk_content, .. } => {
-                                let content = chunk_content.clone();
-                                store.dispatch(
-                                    PeerChunkWriteSetContentAction {
-                                        address: action.address,
-                                        content,
-                                    }
-                                    .into(),
-                                )
-                            }
-                            PeerBinaryMessageWriteState::Ready { .. } => store.dispatch(
-                                PeerBinaryMessageWriteReadyAction {
-                                    address: action.address,
-                                }
-                                .into(),
-                            ),
-                            _ => {}
-                        },
-                        _ => {}
+                        } => binary_message_state,
+                        _ => return,
                     },
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_write:
+                            PeerMessageWriteState {
+                                current: binary_message_state,
+                                ..
+                            },
+                        ..
+                    }) => binary_message_state,
+                    _ => return,
+                };
+
+                match binary_message_state {
+                    PeerBinaryMessageWriteState::Pending { chunk_content, .. } => {
+                        let content = chunk_content.clone();
+                        store.dispatch(
+                            PeerChunkWriteSetContentAction {
+                                address: action.address,
+                                content,
+                            }
+                            .into(),
+                        )
+                    }
+                    PeerBinaryMessageWriteState::Ready { .. } => store.dispatch(
+                        PeerBinaryMessageWriteReadyAction {
+                            address: action.address,
+                        }
+                        .into(),
+                    ),
                     _ => {}
                 }
             }
@@ -7,7 +7,8 @@ use crate::{
     peer::{
         chunk::write::peer_chunk_write_state::{PeerChunkWrite, PeerChunkWriteState},
         handshaking::{PeerHandshaking, PeerHandshakingStatus},
-        PeerStatus,
+        message::write::PeerMessageWriteState,
+        PeerHandshaked, PeerStatus,
     },
     State,
 };
@@ -21,7 +22,7 @@ pub fn peer_binary_message_write_reducer(state: &mut State, action: &ActionWithI
     match &action.action {
         Action::PeerBinaryMessageWriteSetContent(action) => {
             if let Some(peer) = state.peers.get_mut(&action.address) {
-                match &mut peer.status {
+                let binary_message_state = match &mut peer.status {
                     PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
                         PeerHandshakingStatus::MetadataMessageWritePending {
                             binary_message_state,
@@ -30,33 +31,38 @@ pub fn peer_binary_message_write_reducer(state: &mut State, action: &ActionWithI
                         | PeerHandshakingStatus::AckMessageWritePending {
                             binary_message_state,
                             ..
-                        } => {
-                            if let PeerBinaryMessageWriteState::Init { crypto } =
-                                binary_message_state
-                            {
-                                let next_chunk_pos =
-                                    cmp::min(MAX_UNENCRYPTED_CHUNK_SIZE, action.message.len());
-                                let (chunk_content, rest_of_message_content) =
-                                    action.message.split_at(next_chunk_pos);
-                                *binary_message_state = PeerBinaryMessageWriteState::Pending {
-                                    chunk_content: chunk_content.to_vec(),
-                                    rest_of_message_content: rest_of_message_content.to_vec(),
-                                    chunk: PeerChunkWrite {
-                                        crypto: crypto.clone(),
-                                        state: PeerChunkWriteState::Init,
-                                    },
-                                };
-                            }
-                        }
-                        _ => {}
+                        } => binary_message_state,
+                        _ => return,
                     },
-                    _ => {}
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_write:
+                            PeerMessageWriteState {
+                                current: binary_message_state,
+                                ..
+                            },
+                        ..
+                    }) => binary_message_state,
+                    _ => return,
+                };
+
+                if let PeerBinaryMessageWriteState::Init { crypto } = binary_message_state {
+                    let next_chunk_pos = cmp::min(MAX_UNENCRYPTED_CHUNK_SIZE, action.message.len());
+                    let (chunk_content, rest_of_message_content) =
+                        action.message.split_at(next_chunk_pos);
+                    *binary_message_state = PeerBinaryMessageWriteState::Pending {
+                        chunk_content: chunk_content.to_vec(),
+                        rest_of_message_content: rest_of_message_content.to_vec(),
+                        chunk: PeerChunkWrite {
+                            crypto: crypto.clone(),
+                            state: PeerChunkWriteState::Init,
+                        },
+                    };
                 }
             }
         }
         Action::PeerBinaryMessageWriteNextChunk(action) => {
             if let Some(peer) = state.peers.get_mut(&action.address) {
-                match &mut peer.status {
+                let binary_message_state = match &mut peer.status {
                     PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
                         PeerHandshakingStatus::MetadataMessageWritePending {
                             binary_message_state,
@@ -65,38 +71,44 @@ pub fn peer_binary_message_write_reducer(state: &mut State, action: &ActionWithI
                         | PeerHandshakingStatus::AckMessageWritePending {
                             binary_message_state,
                             ..
-                        } => {
-                            if let PeerBinaryMessageWriteState::Pending {
-                                rest_of_message_content,
-                                chunk: PeerChunkWrite { crypto, .. },
-                                ..
-                            } = binary_message_state
-                            {
-                                if !rest_of_message_content.is_empty() {
-                                    let next_chunk_pos = cmp::min(
-                                        MAX_UNENCRYPTED_CHUNK_SIZE,
-                                        rest_of_message_content.len(),
-                                    );
-                                    let (chunk_content, rest_of_message_content) =
-                                        rest_of_message_content.split_at(next_chunk_pos);
-                                    *binary_message_state = PeerBinaryMessageWriteState::Pending {
-                                        chunk_content: chunk_content.to_vec(),
-                                        rest_of_message_content: rest_of_message_content.to_vec(),
-                                        chunk: PeerChunkWrite {
-                                            crypto: crypto.clone(),
-                                            state: PeerChunkWriteState::Init,
-                                        },
-                                    };
-                                } else {
-                                    *binary_message_state = PeerBinaryMessageWriteState::Ready {
-                                        crypto: crypto.clone(),
-                                    };
-                                }
-                            }
-                        }
-                        _ => {}
+                        } => binary_message_state,
+                        _ => return,
                     },
-                    _ => {}
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_write:
+                            PeerMessageWriteState {
+                                current: binary_message_state,
+                                ..
+                            },
+                        ..
+                    }) => binary_message_state,
+                    _ => return,
+                };
+
+                if let PeerBinaryMessageWriteState::Pending {
+                    rest_of_message_content,
+                    chunk: PeerChunkWrite { crypto, .. },
+                    ..
+                } = binary_message_state
+                {
+                    if !rest_of_message_content.is_empty() {
+                        let next_chunk_pos =
+                            cmp::min(MAX_UNENCRYPTED_CHUNK_SIZE, rest_of_message_content.len());
+                        let (chunk_content, rest_of_message_content) =
+                            rest_of_message_content.split_at(next_chunk_pos);
+                        *binary_message_state = PeerBinaryMessageWriteState::Pending {
+                            chunk_content: chunk_content.to_vec(),
+                            rest_of_message_content: rest_of_message_content.to_vec(),
+                            chunk: PeerChunkWrite {
+                                crypto: crypto.clone(),
+                                state: PeerChunkWriteState::Init,
+                            },
+                        };
+                    } else {
+                        *binary_message_state = PeerBinaryMessageWriteState::Ready {
+                            crypto: crypto.clone(),
+                        };
+                    }
                 }
             }
         }
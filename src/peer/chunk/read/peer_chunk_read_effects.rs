@@ -9,7 +9,8 @@ use crate::{
             },
             peer_chunk_read_state::{PeerChunkReadError, PeerChunkReadState},
         },
-        PeerStatus, PeerTryReadAction,
+        message::read::PeerMessageReadState,
+        PeerHandshaked, PeerStatus, PeerTryReadAction,
     },
     service::Service,
     State,
@@ -61,6 +62,21 @@ pub fn peer_chunk_read_effects<S>(
                         },
                         _ => return,
                     }
+                    PeerStatus::Handshaked(PeerHandshaked { message_read: PeerMessageReadState::Pending { binary_message_state }, .. }) => match binary_message_state {
+                        crate::peer::binary_message::read::peer_binary_message_read_state::PeerBinaryMessageReadState::PendingFirstChunk { chunk } |
+                        crate::peer::binary_message::read::peer_binary_message_read_state::PeerBinaryMessageReadState::Pending { chunk, .. } => match &chunk.state {
+                            PeerChunkReadState::PendingSize { .. } | PeerChunkReadState::PendingBody { .. } => {
+                                store.dispatch(PeerTryReadAction { address: action.address }.into());
+                            }
+                            PeerChunkReadState::EncryptedReady { chunk_encrypted: chunk_content_encrypted } =>
+                                match chunk.crypto.decrypt(&chunk_content_encrypted) {
+                                    Ok(decrypted_bytes) => store.dispatch(PeerChunkReadDecryptAction { address: action.address, decrypted_bytes }.into()),
+                                    Err(err) => store.dispatch(PeerChunkReadErrorAction { address: action.address, error: PeerChunkReadError::from(err) }.into()),
+                                }
+                            _ => {}
+                        }
+                        _ => {}
+                    },
                     _ => return,
                 };
             }
@@ -82,6 +98,16 @@ pub fn peer_chunk_read_effects<S>(
                         },
                         _ => return,
                     }
+                    PeerStatus::Handshaked(PeerHandshaked { message_read: PeerMessageReadState::Pending { binary_message_state }, .. }) => match binary_message_state {
+                        crate::peer::binary_message::read::peer_binary_message_read_state::PeerBinaryMessageReadState::PendingFirstChunk { chunk } |
+                        crate::peer::binary_message::read::peer_binary_message_read_state::PeerBinaryMessageReadState::Pending { chunk, .. } => match &chunk.state {
+                            PeerChunkReadState::Ready { .. } => {
+                                store.dispatch(PeerChunkReadReadyAction { address: action.address }.into());
+                            }
+                            _ => {}
+                        }
+                        _ => {}
+                    },
                     _ => return,
                 };
             }
@@ -3,7 +3,10 @@ use tezos_messages::p2p::binary_message::CONTENT_LENGTH_FIELD_BYTES;
 
 use crate::{
     action::Action,
-    peer::{chunk::read::peer_chunk_read_state::PeerChunkReadState, PeerStatus},
+    peer::{
+        chunk::read::peer_chunk_read_state::PeerChunkReadState,
+        message::read::PeerMessageReadState, PeerHandshaked, PeerStatus,
+    },
     State,
 };
 
@@ -22,6 +25,11 @@ pub fn peer_chunk_read_reducer(state: &mut State, action: &ActionWithId<Action>)
                         },
                         _ => return,
                     }
+                    PeerStatus::Handshaked(PeerHandshaked { message_read: PeerMessageReadState::Pending { binary_message_state }, .. }) => match binary_message_state {
+                        crate::peer::binary_message::read::peer_binary_message_read_state::PeerBinaryMessageReadState::PendingFirstChunk { chunk } |
+                        crate::peer::binary_message::read::peer_binary_message_read_state::PeerBinaryMessageReadState::Pending { chunk, .. } => &mut chunk.state,
+                        _ => return,
+                    },
                     _ => return,
                 };
 
@@ -81,6 +89,30 @@ pub fn peer_chunk_read_reducer(state: &mut State, action: &ActionWithId<Action>)
                         },
                         _ => return,
                     }
+                    PeerStatus::Handshaked(PeerHandshaked { message_read: PeerMessageReadState::Pending { binary_message_state }, .. }) => match binary_message_state {
+                        crate::peer::binary_message::read::peer_binary_message_read_state::PeerBinaryMessageReadState::PendingFirstChunk { chunk } |
+                        crate::peer::binary_message::read::peer_binary_message_read_state::PeerBinaryMessageReadState::Pending { chunk, .. } => match &mut chunk.state {
+                            PeerChunkReadState::PendingSize { buffer } => {
+                                if buffer.len() + action.bytes.len() <= CONTENT_LENGTH_FIELD_BYTES {
+                                    buffer.extend_from_slice(&action.bytes);
+                                    if buffer.len() == CONTENT_LENGTH_FIELD_BYTES {
+                                        let size = ((u16::from(buffer[0]) << 8) + u16::from(buffer[1])).into();
+                                        chunk.state = PeerChunkReadState::PendingBody { buffer: Vec::new(), size };
+                                    }
+                                }
+                            }
+                            PeerChunkReadState::PendingBody { buffer, size } => {
+                                if buffer.len() + action.bytes.len() <= *size {
+                                    buffer.extend_from_slice(&action.bytes);
+                                    if buffer.len() == *size {
+                                        chunk.state = PeerChunkReadState::EncryptedReady { chunk_encrypted: buffer.clone() };
+                                    }
+                                }
+                            }
+                            _ => {}
+                        }
+                        _ => {}
+                    },
                     _ => return,
                 }
             }
@@ -102,6 +134,16 @@ pub fn peer_chunk_read_reducer(state: &mut State, action: &ActionWithId<Action>)
                         },
                         _ => {},
                     }
+                    PeerStatus::Handshaked(PeerHandshaked { message_read: PeerMessageReadState::Pending { binary_message_state }, .. }) => match binary_message_state {
+                        crate::peer::binary_message::read::peer_binary_message_read_state::PeerBinaryMessageReadState::PendingFirstChunk { chunk } |
+                        crate::peer::binary_message::read::peer_binary_message_read_state::PeerBinaryMessageReadState::Pending { chunk, .. } => {
+                            if let PeerChunkReadState::EncryptedReady { .. } = &chunk.state {
+                                chunk.state = PeerChunkReadState::Ready { chunk: action.decrypted_bytes.clone() };
+                                chunk.crypto.increment_nonce();
+                            }
+                        },
+                        _ => {},
+                    },
                     _ => {},
                 }
             }
@@ -5,7 +5,7 @@ use crate::{
     action::Action,
     peer::{
         binary_message::write::peer_binary_message_write_state::PeerBinaryMessageWriteState,
-        PeerStatus, PeerTryWriteAction,
+        message::write::PeerMessageWriteState, PeerHandshaked, PeerStatus, PeerTryWriteAction,
     },
     service::Service,
     State,
@@ -46,6 +46,15 @@ pub fn peer_chunk_write_effects<S>(
                         },
                         _ => return,
                     }
+                    PeerStatus::Handshaked(PeerHandshaked { message_write: PeerMessageWriteState { current: binary_message_state, .. }, .. }) => match binary_message_state {
+                        PeerBinaryMessageWriteState::Pending { chunk: PeerChunkWrite { crypto,  state: PeerChunkWriteState::UnencryptedContent { content },  }, .. } => {
+                            match crypto.encrypt(&content) {
+                                Ok(encrypted_content) => store.dispatch(PeerChunkWriteEncryptContentAction { address: action.address, encrypted_content }.into()),
+                                Err(err) => store.dispatch(PeerChunkWriteErrorAction { address: action.address, error: PeerChunkWriteError::from(err) }.into()),
+                            };
+                        },
+                        _ => return,
+                    },
                     _ => return,
                 };
             }
@@ -65,6 +74,14 @@ pub fn peer_chunk_write_effects<S>(
                         }
                         _ => {},
                     }
+                    PeerStatus::Handshaked(PeerHandshaked { message_write: PeerMessageWriteState { current: binary_message_state, .. }, .. }) => match binary_message_state {
+                        PeerBinaryMessageWriteState::Pending { chunk: PeerChunkWrite { state: PeerChunkWriteState::EncryptedContent { content }, .. }, .. } =>
+                            match BinaryChunk::from_content(&content) {
+                                Ok(chunk) => store.dispatch(PeerChunkWriteCreateChunkAction { address: action.address, chunk }.into()),
+                                Err(err) => store.dispatch(PeerChunkWriteErrorAction { address: action.address, error: err.into() }.into()),
+                            }
+                        _ => {},
+                    }
                     _ => {},
                 };
             }
@@ -85,6 +102,12 @@ pub fn peer_chunk_write_effects<S>(
                         },
                         _ => return,
                     }
+                    PeerStatus::Handshaked(PeerHandshaked { message_write: PeerMessageWriteState { current: binary_message_state, .. }, .. }) => match binary_message_state {
+                        PeerBinaryMessageWriteState::Pending { chunk: PeerChunkWrite { state: PeerChunkWriteState::Pending { .. }, ..}, .. } => {
+                            store.dispatch(PeerTryWriteAction { address: action.address }.into());
+                        }
+                        _ => {}
+                    },
                     _ => return,
                 }
             }
@@ -117,6 +140,18 @@ pub fn peer_chunk_write_effects<S>(
                         },
                         _ => return,
                     }
+                    PeerStatus::Handshaked(PeerHandshaked { message_write: PeerMessageWriteState { current: binary_message_state, .. }, .. }) => match binary_message_state {
+                        PeerBinaryMessageWriteState::Pending { chunk, .. } => match &chunk.state {
+                            PeerChunkWriteState::Pending { .. } => {
+                                store.dispatch(PeerTryWriteAction { address: action.address }.into());
+                            }
+                            PeerChunkWriteState::Ready { .. } => {
+                                store.dispatch(PeerChunkWriteReadyAction { address: action.address }.into());
+                            }
+                            _ => {}
+                        }
+                        _ => {}
+                    },
                     _ => return,
                 };
             }
@@ -6,7 +6,8 @@ use crate::{
         binary_message::write::peer_binary_message_write_state::PeerBinaryMessageWriteState,
         chunk::write::peer_chunk_write_state::PeerChunkWriteState,
         handshaking::{PeerHandshaking, PeerHandshakingStatus},
-        PeerStatus,
+        message::write::PeerMessageWriteState,
+        PeerHandshaked, PeerStatus,
     },
     State,
 };
@@ -31,6 +32,14 @@ pub fn peer_chunk_write_reducer(state: &mut State, action: &ActionWithId<Action>
                         } => &mut chunk.state,
                         _ => return,
                     },
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_write:
+                            PeerMessageWriteState {
+                                current: PeerBinaryMessageWriteState::Pending { chunk, .. },
+                                ..
+                            },
+                        ..
+                    }) => &mut chunk.state,
                     _ => return,
                 };
 
@@ -63,6 +72,22 @@ pub fn peer_chunk_write_reducer(state: &mut State, action: &ActionWithId<Action>
                         },
                         _ => {}
                     },
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_write:
+                            PeerMessageWriteState {
+                                current: PeerBinaryMessageWriteState::Pending { chunk, .. },
+                                ..
+                            },
+                        ..
+                    }) => match chunk.state {
+                        PeerChunkWriteState::UnencryptedContent { .. } => {
+                            chunk.state = PeerChunkWriteState::EncryptedContent {
+                                content: action.encrypted_content.clone(),
+                            };
+                            chunk.crypto.increment_nonce();
+                        }
+                        _ => {}
+                    },
                     _ => {}
                 }
             }
@@ -91,6 +116,17 @@ pub fn peer_chunk_write_reducer(state: &mut State, action: &ActionWithId<Action>
                         },
                         _ => return,
                     },
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_write:
+                            PeerMessageWriteState {
+                                current: PeerBinaryMessageWriteState::Pending { chunk, .. },
+                                ..
+                            },
+                        ..
+                    }) => match chunk.state {
+                        PeerChunkWriteState::EncryptedContent { .. } => &mut chunk.state,
+                        _ => return,
+                    },
                     _ => return,
                 };
 
@@ -102,35 +138,39 @@ pub fn peer_chunk_write_reducer(state: &mut State, action: &ActionWithId<Action>
         }
         Action::PeerChunkWritePart(action) => {
             if let Some(peer) = state.peers.get_mut(&action.address) {
-                match &mut peer.status {
-                    PeerStatus::Handshaking(PeerHandshaking { status, .. }) => {
-                        let chunk_state = match status {
-                            PeerHandshakingStatus::ConnectionMessageWritePending {
-                                chunk_state,
-                                ..
-                            } => chunk_state,
-                            PeerHandshakingStatus::MetadataMessageWritePending {
-                                binary_message_state:
-                                    PeerBinaryMessageWriteState::Pending { chunk, .. },
-                                ..
-                            }
-                            | PeerHandshakingStatus::AckMessageWritePending {
-                                binary_message_state:
-                                    PeerBinaryMessageWriteState::Pending { chunk, .. },
+                let chunk_state = match &mut peer.status {
+                    PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
+                        PeerHandshakingStatus::ConnectionMessageWritePending {
+                            chunk_state,
+                            ..
+                        } => chunk_state,
+                        PeerHandshakingStatus::MetadataMessageWritePending {
+                            binary_message_state: PeerBinaryMessageWriteState::Pending { chunk, .. },
+                            ..
+                        }
+                        | PeerHandshakingStatus::AckMessageWritePending {
+                            binary_message_state: PeerBinaryMessageWriteState::Pending { chunk, .. },
+                            ..
+                        } => &mut chunk.state,
+                        _ => return,
+                    },
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_write:
+                            PeerMessageWriteState {
+                                current: PeerBinaryMessageWriteState::Pending { chunk, .. },
                                 ..
-                            } => &mut chunk.state,
-                            _ => return,
-                        };
+                            },
+                        ..
+                    }) => &mut chunk.state,
+                    _ => return,
+                };
 
-                        if let PeerChunkWriteState::Pending { chunk, written } = chunk_state {
-                            if *written + action.written < chunk.raw().len() {
-                                *written += action.written;
-                            } else {
-                                *chunk_state = PeerChunkWriteState::Ready;
-                            }
-                        }
+                if let PeerChunkWriteState::Pending { chunk, written } = chunk_state {
+                    if *written + action.written < chunk.raw().len() {
+                        *written += action.written;
+                    } else {
+                        *chunk_state = PeerChunkWriteState::Ready;
                     }
-                    _ => {}
                 }
             }
         }
@@ -18,3 +18,21 @@ pub struct PeerConnectionIncomingAcceptSuccessAction {
 pub struct PeerConnectionIncomingAcceptErrorAction {
     pub error: PeerConnectionIncomingAcceptError,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum PeerConnectionIncomingRejectedReason {
+    /// We are in private mode and the peer isn't trusted.
+    NotTrusted,
+    Graylisted,
+    PeersConnectedMaxBoundReached,
+    PeersIncomingMaxBoundReached,
+    PeersPendingMaxBoundReached,
+}
+
+/// Incoming connection was accepted, but it will be dropped right away.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerConnectionIncomingRejectedAction {
+    pub token: PeerToken,
+    pub address: SocketAddr,
+    pub reason: PeerConnectionIncomingRejectedReason,
+}
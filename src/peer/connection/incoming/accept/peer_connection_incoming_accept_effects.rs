@@ -7,7 +7,8 @@ use crate::{action::Action, State};
 
 use super::{
     PeerConnectionIncomingAcceptAction, PeerConnectionIncomingAcceptErrorAction,
-    PeerConnectionIncomingAcceptSuccessAction,
+    PeerConnectionIncomingAcceptSuccessAction, PeerConnectionIncomingRejectedAction,
+    PeerConnectionIncomingRejectedReason,
 };
 
 pub fn peer_connection_incoming_accept_effects<S>(
@@ -21,10 +22,41 @@ pub fn peer_connection_incoming_accept_effects<S>(
             store.dispatch(PeerConnectionIncomingAcceptAction {}.into());
         }
         Action::PeerConnectionIncomingAccept(_) => {
-            // TODO: check peer thresholds.
             match store.service.mio().peer_connection_incoming_accept() {
                 Ok((peer_token, peer)) => {
                     let peer_address = peer.address;
+                    let state = store.state.get();
+                    let reject_reason = if state.config.private_node
+                        && !state.is_trusted_ip(peer_address.ip())
+                    {
+                        Some(PeerConnectionIncomingRejectedReason::NotTrusted)
+                    } else if state.is_graylisted(&peer_address) {
+                        Some(PeerConnectionIncomingRejectedReason::Graylisted)
+                    } else if state.is_trusted_ip_exempt(peer_address.ip()) {
+                        // trusted peers aren't limited by peer thresholds.
+                        None
+                    } else if state.peers_connected_len() >= state.config.peers_connected_max {
+                        Some(PeerConnectionIncomingRejectedReason::PeersConnectedMaxBoundReached)
+                    } else if state.peers_incoming_len() >= state.config.peers_incoming_max {
+                        Some(PeerConnectionIncomingRejectedReason::PeersIncomingMaxBoundReached)
+                    } else if state.peers_pending_len() >= state.config.peers_pending_max {
+                        Some(PeerConnectionIncomingRejectedReason::PeersPendingMaxBoundReached)
+                    } else {
+                        None
+                    };
+
+                    if let Some(reason) = reject_reason {
+                        store.dispatch(
+                            PeerConnectionIncomingRejectedAction {
+                                token: peer_token,
+                                address: peer_address,
+                                reason,
+                            }
+                            .into(),
+                        );
+                        return;
+                    }
+
                     store.dispatch(
                         PeerConnectionIncomingAcceptSuccessAction {
                             token: peer_token,
@@ -57,6 +89,10 @@ pub fn peer_connection_incoming_accept_effects<S>(
             // connection event, until we have new incoming connections.
             store.dispatch(PeerConnectionIncomingAcceptAction {}.into());
         }
+        Action::PeerConnectionIncomingRejected(action) => {
+            store.service.mio().peer_disconnect(action.token);
+            store.dispatch(PeerConnectionIncomingAcceptAction {}.into());
+        }
         _ => {}
     }
 }
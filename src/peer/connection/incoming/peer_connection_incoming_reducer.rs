@@ -10,7 +10,7 @@ pub fn peer_connection_incoming_reducer(state: &mut State, action: &ActionWithId
         Action::PeerConnectionIncomingSuccess(action) => {
             if let Some(peer) = state.peers.get_mut(&action.address) {
                 if let PeerStatus::Connecting(PeerConnectionState::Incoming(
-                    PeerConnectionIncomingState::Pending { token },
+                    PeerConnectionIncomingState::Pending { token, .. },
                 )) = peer.status
                 {
                     peer.status = PeerStatus::Connecting(
@@ -1,6 +1,7 @@
 use redux_rs::{ActionWithId, Store};
 
 use crate::peer::connection::PeerConnectionState;
+use crate::peer::disconnection::PeerDisconnectAction;
 use crate::peer::handshaking::PeerHandshakingInitAction;
 use crate::peer::PeerStatus;
 use crate::service::{MioService, RandomnessService, Service};
@@ -10,6 +11,7 @@ use super::{
     PeerConnectionOutgoingErrorAction, PeerConnectionOutgoingInitAction,
     PeerConnectionOutgoingPendingAction, PeerConnectionOutgoingRandomInitAction,
     PeerConnectionOutgoingState, PeerConnectionOutgoingSuccessAction,
+    PEER_CONNECTION_BACKOFF_EXP_MAX, PEER_TRUSTED_CONNECTION_BACKOFF_EXP_MAX,
 };
 
 pub fn peer_connection_outgoing_effects<S>(
@@ -20,13 +22,40 @@ pub fn peer_connection_outgoing_effects<S>(
 {
     match &action.action {
         Action::PeerConnectionOutgoingRandomInit(_) => {
-            let addresses = store
-                .state
-                .get()
+            let state = store.state.get();
+            let potential = state
                 .peers
                 .iter()
                 .filter(|(_, peer)| matches!(&peer.status, PeerStatus::Potential))
-                .map(|(addr, _)| *addr)
+                .filter(|(addr, _)| !state.is_graylisted(addr))
+                // e.g. trusted peer, which has connected to us.
+                .filter(|(addr, _)| !state.is_listen_address_connected(addr))
+                .filter(|(addr, peer)| {
+                    let max_exp = if state.is_trusted_address(addr) {
+                        PEER_TRUSTED_CONNECTION_BACKOFF_EXP_MAX
+                    } else {
+                        PEER_CONNECTION_BACKOFF_EXP_MAX
+                    };
+                    peer.connection_attempts
+                        .backoff_until(state.config.peer_connection_backoff, max_exp)
+                        .map_or(true, |until| state.time >= until)
+                });
+
+            // trusted peers are connected to first and regardless of
+            // peer thresholds.
+            if let Some((address, _)) = potential
+                .clone()
+                .find(|(addr, _)| state.is_trusted_address(addr))
+            {
+                let address = *address;
+                store.dispatch(PeerConnectionOutgoingInitAction { address }.into());
+                return;
+            }
+            if !state.peers_can_connect() {
+                return;
+            }
+            let addresses = potential
+                .map(|(addr, _)| (*addr, state.peer_score(addr)))
                 .collect::<Vec<_>>();
 
             if let Some(address) = store.service.randomness().choose_peer(&addresses) {
@@ -46,8 +75,8 @@ pub fn peer_connection_outgoing_effects<S>(
             });
         }
         Action::PeerConnectionOutgoingPending(_) => {
-            // try to connect to next random peer.
-            // TODO: maybe check peer thresholds?
+            // try to connect to next random peer. Peer thresholds
+            // are checked in `PeerConnectionOutgoingRandomInit`.
             store.dispatch(PeerConnectionOutgoingRandomInitAction {}.into());
         }
         Action::P2pPeerEvent(event) => {
@@ -82,8 +111,14 @@ pub fn peer_connection_outgoing_effects<S>(
             }
             .into(),
         ),
-        Action::PeerConnectionOutgoingError(_) => {
-            store.dispatch(PeerConnectionOutgoingRandomInitAction {}.into());
+        Action::PeerConnectionOutgoingError(action) => {
+            // Disconnection will try to connect to the next random peer.
+            store.dispatch(
+                PeerDisconnectAction {
+                    address: action.address,
+                }
+                .into(),
+            );
         }
         _ => {}
     }
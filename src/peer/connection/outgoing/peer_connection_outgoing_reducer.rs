@@ -1,6 +1,9 @@
 use redux_rs::ActionWithId;
 
+use crate::io_error_kind::IOErrorKind;
 use crate::peer::connection::PeerConnectionState;
+use crate::peer::handshaking::PeerHandshaking;
+use crate::peers::timeouts::PeerTimeout;
 use crate::{
     action::Action,
     peer::{Peer, PeerStatus},
@@ -12,11 +15,14 @@ use super::PeerConnectionOutgoingState;
 pub fn peer_connection_outgoing_reducer(state: &mut State, action: &ActionWithId<Action>) {
     match &action.action {
         Action::PeerConnectionOutgoingInit(action) => {
+            let time = state.time;
             let peer = state.peers.entry(action.address).or_insert_with(|| Peer {
                 status: PeerStatus::Potential,
+                connection_attempts: Default::default(),
             });
             if matches!(peer.status, PeerStatus::Potential) {
                 peer.status = PeerStatus::Connecting(PeerConnectionOutgoingState::Idle.into());
+                peer.connection_attempts.last_attempt_at = Some(time);
             }
         }
         Action::PeerConnectionOutgoingPending(action) => {
@@ -30,6 +36,7 @@ pub fn peer_connection_outgoing_reducer(state: &mut State, action: &ActionWithId
                     peer.status = PeerStatus::Connecting(
                         PeerConnectionOutgoingState::Pending {
                             token: action.token,
+                            started_at: state.time,
                         }
                         .into(),
                     );
@@ -52,13 +59,14 @@ pub fn peer_connection_outgoing_reducer(state: &mut State, action: &ActionWithId
                         }
                         .into(),
                     );
+                    peer.connection_attempts.failed(Some(action.error));
                 }
             }
         }
         Action::PeerConnectionOutgoingSuccess(action) => {
             if let Some(peer) = state.peers.get_mut(&action.address) {
                 if let PeerStatus::Connecting(PeerConnectionState::Outgoing(
-                    PeerConnectionOutgoingState::Pending { token },
+                    PeerConnectionOutgoingState::Pending { token, .. },
                 )) = peer.status
                 {
                     peer.status = PeerStatus::Connecting(
@@ -67,6 +75,46 @@ pub fn peer_connection_outgoing_reducer(state: &mut State, action: &ActionWithId
                 }
             }
         }
+        Action::PeerTimeout(action) => {
+            if let PeerTimeout::Connecting = action.timeout {
+                if let Some(peer) = state.peers.get_mut(&action.address) {
+                    if let PeerStatus::Connecting(PeerConnectionState::Outgoing(
+                        PeerConnectionOutgoingState::Pending { .. },
+                    )) = peer.status
+                    {
+                        peer.connection_attempts.failed(Some(IOErrorKind::TimedOut));
+                    }
+                }
+            }
+        }
+        Action::PeerHandshakingError(action) => {
+            // Otherwise we would keep redialing the peer, which we can
+            // connect to, but can't handshake with.
+            if let Some(peer) = state.peers.get_mut(&action.address) {
+                if let PeerStatus::Handshaking(PeerHandshaking {
+                    incoming: false, ..
+                }) = peer.status
+                {
+                    peer.connection_attempts.failed(None);
+                }
+            }
+        }
+        Action::PeerHandshakingFinish(action) => {
+            if let Some(peer) = state.peers.get_mut(&action.address) {
+                peer.connection_attempts.count = 0;
+            }
+        }
+        Action::PeerDisconnected(action) => {
+            // Connection got closed before it was established.
+            if let Some(peer) = state.peers.get_mut(&action.address) {
+                if let PeerStatus::Connecting(PeerConnectionState::Outgoing(
+                    PeerConnectionOutgoingState::Pending { .. },
+                )) = peer.status
+                {
+                    peer.connection_attempts.failed(None);
+                }
+            }
+        }
         _ => {}
     }
 }
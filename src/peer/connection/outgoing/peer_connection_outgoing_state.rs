@@ -1,22 +1,68 @@
 use crate::io_error_kind::IOErrorKind;
 use crate::peer::PeerToken;
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum PeerConnectionOutgoingState {
     Idle,
-    Pending { token: PeerToken },
-    Error { error: IOErrorKind },
-    Success { token: PeerToken },
+    Pending {
+        token: PeerToken,
+        started_at: SystemTime,
+    },
+    Error {
+        error: IOErrorKind,
+    },
+    Success {
+        token: PeerToken,
+    },
 }
 
 impl PeerConnectionOutgoingState {
     pub fn token(&self) -> Option<PeerToken> {
         match self {
             Self::Idle => None,
-            Self::Pending { token } => Some(*token),
+            Self::Pending { token, .. } => Some(*token),
             Self::Error { .. } => None,
             Self::Success { token } => Some(*token),
         }
     }
 }
+
+pub const PEER_CONNECTION_BACKOFF_EXP_MAX: u32 = 16;
+/// Trusted peers should be reconnected soon after they come back
+/// online, so their backoff grows only up to `backoff * 2^this`.
+pub const PEER_TRUSTED_CONNECTION_BACKOFF_EXP_MAX: u32 = 2;
+
+/// History of the outgoing connection attempts to the peer.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct PeerConnectionAttempts {
+    /// Number of failed attempts (including failed handshakes) since
+    /// the last successful handshake.
+    pub count: u32,
+    /// Error of the last failed attempt.
+    ///
+    /// `None` if it isn't an io error, e.g. mio reported closed
+    /// connection before it was established or handshake failed.
+    pub last_error: Option<IOErrorKind>,
+    pub last_attempt_at: Option<SystemTime>,
+}
+
+impl PeerConnectionAttempts {
+    pub fn failed(&mut self, error: Option<IOErrorKind>) {
+        self.count += 1;
+        self.last_error = error;
+    }
+
+    /// Time until which we shouldn't try to connect to the peer again.
+    ///
+    /// Backoff doubles with each consecutive failed attempt, up to
+    /// `backoff * 2^max_exp`.
+    pub fn backoff_until(&self, backoff: Duration, max_exp: u32) -> Option<SystemTime> {
+        if self.count == 0 {
+            return None;
+        }
+        let exp = (self.count - 1).min(max_exp);
+        Some(self.last_attempt_at? + backoff * (1 << exp))
+    }
+}
@@ -39,6 +39,8 @@ pub fn peer_disconnection_effects<S>(
                 if matches!(&peer.status, PeerStatus::Disconnected) {
                     let address = action.address;
 
+                    // trusted peer is kept as potential by `PeersRemove`, so
+                    // random init below will reconnect to it.
                     store.dispatch(PeersRemoveAction { address }.into());
                     store.dispatch(PeerConnectionOutgoingRandomInitAction {}.into());
                 }
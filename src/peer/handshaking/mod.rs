@@ -9,3 +9,6 @@ pub use peer_handshaking_reducer::*;
 
 mod peer_handshaking_effects;
 pub use peer_handshaking_effects::*;
+
+#[cfg(test)]
+mod peer_handshaking_tests;
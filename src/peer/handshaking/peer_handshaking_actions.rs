@@ -1,9 +1,13 @@
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 
+use crypto::crypto_box::PublicKey;
 use tezos_messages::p2p::{
     binary_message::BinaryChunk,
-    encoding::{ack::AckMessage, connection::ConnectionMessage, metadata::MetadataMessage},
+    encoding::{
+        ack::AckMessage, connection::ConnectionMessage, metadata::MetadataMessage,
+        version::NetworkVersion,
+    },
 };
 
 use super::{PeerCrypto, PeerHandshakingError};
@@ -92,6 +96,8 @@ pub struct PeerHandshakingMetadataMessageDecodeAction {
 pub struct PeerHandshakingAckMessageInitAction {
     pub address: SocketAddr,
     pub message: AckMessage,
+    /// Chosen network version. `None` if we are sending `Nack`.
+    pub version: Option<NetworkVersion>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -119,4 +125,6 @@ pub struct PeerHandshakingAckMessageDecodeAction {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PeerHandshakingFinishAction {
     pub address: SocketAddr,
+    pub version: NetworkVersion,
+    pub public_key: PublicKey,
 }
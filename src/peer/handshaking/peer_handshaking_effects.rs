@@ -1,8 +1,10 @@
 use crypto::crypto_box::{CryptoKey, PrecomputedKey, PublicKey};
 use crypto::nonce::{generate_nonces, NoncePair};
+use crypto::proof_of_work::check_proof_of_work;
 use redux_rs::{ActionWithId, Store};
+use std::net::SocketAddr;
 use tezos_messages::p2p::binary_message::{BinaryChunk, BinaryRead, BinaryWrite};
-use tezos_messages::p2p::encoding::ack::AckMessage;
+use tezos_messages::p2p::encoding::ack::{AckMessage, NackInfo};
 use tezos_messages::p2p::encoding::connection::ConnectionMessage;
 use tezos_messages::p2p::encoding::metadata::MetadataMessage;
 
@@ -13,12 +15,15 @@ use crate::peer::binary_message::write::peer_binary_message_write_actions::PeerB
 use crate::peer::chunk::read::peer_chunk_read_actions::PeerChunkReadInitAction;
 use crate::peer::chunk::read::peer_chunk_read_state::PeerChunkReadState;
 use crate::peer::chunk::write::PeerChunkWriteSetContentAction;
+use crate::peer::disconnection::PeerDisconnectAction;
 use crate::peer::handshaking::{
     PeerCrypto, PeerHandshakingConnectionMessageEncodeAction,
     PeerHandshakingConnectionMessageInitAction, PeerHandshakingConnectionMessageWriteAction,
     PeerHandshakingMetadataMessageInitAction,
 };
-use crate::peer::PeerStatus;
+use crate::peer::{PeerHandshaked, PeerStatus};
+use crate::peers::add::multi::PeersAddMultiAction;
+use crate::peers::graylist::{PeerGraylistReason, PeersGraylistAddressAction};
 use crate::service::{RandomnessService, Service};
 use crate::State;
 
@@ -33,6 +38,26 @@ use super::{
     PeerHandshakingStatus,
 };
 
+/// Max number of potential peers sent to the peer in `Nack`.
+const NACK_POTENTIAL_PEERS_MAX_LEN: usize = 100;
+
+/// Addresses of handshaked peers, which the nacked peer could try to
+/// connect to instead.
+fn nack_potential_peers(state: &State, nacked_address: SocketAddr) -> Vec<String> {
+    state
+        .peers
+        .iter()
+        .filter(|(address, _)| **address != nacked_address)
+        .filter_map(|(address, peer)| match &peer.status {
+            PeerStatus::Handshaked(PeerHandshaked { port, .. }) => {
+                Some(SocketAddr::new(address.ip(), *port).to_string())
+            }
+            _ => None,
+        })
+        .take(NACK_POTENTIAL_PEERS_MAX_LEN)
+        .collect()
+}
+
 pub fn peer_handshaking_effects<S>(
     store: &mut Store<State, S, Action>,
     action: &ActionWithId<Action>,
@@ -44,7 +69,7 @@ pub fn peer_handshaking_effects<S>(
             let nonce = store.service().randomness().get_nonce(action.address);
             let config = &store.state().config;
             match ConnectionMessage::try_new(
-                config.port,
+                config.p2p_address.port(),
                 &config.identity.public_key,
                 &config.identity.proof_of_work_stamp,
                 nonce.clone(),
@@ -209,10 +234,30 @@ pub fn peer_handshaking_effects<S>(
                                 remote_chunk,
                                 remote_message,
                             },
+                        incoming,
                         ..
                     }) => {
+                        let pow_data = [
+                            remote_message.public_key.as_slice(),
+                            remote_message.proof_of_work_stamp.as_slice(),
+                        ]
+                        .concat();
+                        let pow_target = store.state.get().config.pow_target;
+                        if let Err(err) = check_proof_of_work(&pow_data, pow_target) {
+                            return store.dispatch(
+                                PeerHandshakingErrorAction {
+                                    address: action.address,
+                                    error: PeerHandshakingError::InsufficientProofOfWork(
+                                        err.to_string(),
+                                    ),
+                                }
+                                .into(),
+                            );
+                        }
+
                         let NoncePair { local, remote } =
-                            match generate_nonces(local_chunk.raw(), remote_chunk.raw(), false) {
+                            match generate_nonces(local_chunk.raw(), remote_chunk.raw(), *incoming)
+                            {
                                 Ok(v) => v,
                                 Err(err) => {
                                     store.dispatch(
@@ -240,6 +285,113 @@ pub fn peer_handshaking_effects<S>(
                             }
                         };
 
+                        let peer_id = match public_key.public_key_hash() {
+                            Ok(v) => v,
+                            Err(err) => {
+                                store.dispatch(
+                                    PeerHandshakingErrorAction {
+                                        address: action.address,
+                                        error: err.into(),
+                                    }
+                                    .into(),
+                                );
+                                return;
+                            }
+                        };
+
+                        let state = store.state.get();
+                        // port is only known now for incoming connections.
+                        if state.is_graylisted(&action.address) {
+                            store.dispatch(
+                                PeerHandshakingErrorAction {
+                                    address: action.address,
+                                    error: PeerHandshakingError::Graylisted,
+                                }
+                                .into(),
+                            );
+                            return;
+                        }
+                        if state.config.private_node
+                            && !state.is_trusted_address(&state.peer_listen_address(action.address))
+                        {
+                            store.dispatch(
+                                PeerHandshakingErrorAction {
+                                    address: action.address,
+                                    error: PeerHandshakingError::NotTrusted,
+                                }
+                                .into(),
+                            );
+                            return;
+                        }
+
+                        // address of the connection to drop and the reason.
+                        let identity_error = if peer_id == state.config.identity.peer_id {
+                            Some((action.address, PeerHandshakingError::SelfConnection))
+                        } else if state.is_trusted_peer_id_mismatch(action.address.ip(), &peer_id) {
+                            Some((action.address, PeerHandshakingError::TrustedPeerIdMismatch))
+                        } else {
+                            state
+                                .peers
+                                .iter()
+                                .filter(|(address, _)| **address != action.address)
+                                .find_map(|(address, peer)| match &peer.status {
+                                    PeerStatus::Handshaked(handshaked)
+                                        if handshaked.public_key == public_key =>
+                                    {
+                                        Some((*address, None))
+                                    }
+                                    PeerStatus::Handshaking(handshaking)
+                                        if handshaking
+                                            .status
+                                            .remote_connection_message()
+                                            .map_or(false, |message| {
+                                                message.public_key == remote_message.public_key
+                                            }) =>
+                                    {
+                                        Some((*address, Some(handshaking.incoming)))
+                                    }
+                                    _ => None,
+                                })
+                                .map(|(other_address, other_incoming)| {
+                                    // We and the peer connected to each other at the same
+                                    // time. Both sides keep the connection initiated by
+                                    // the one with the lower peer_id.
+                                    let keep_current = match other_incoming {
+                                        Some(other_incoming) if other_incoming != *incoming => {
+                                            (state.config.identity.peer_id < peer_id) != *incoming
+                                        }
+                                        _ => false,
+                                    };
+                                    if keep_current {
+                                        (
+                                            other_address,
+                                            PeerHandshakingError::DuplicateIdentity {
+                                                connected_address: action.address,
+                                            },
+                                        )
+                                    } else {
+                                        (
+                                            action.address,
+                                            PeerHandshakingError::DuplicateIdentity {
+                                                connected_address: other_address,
+                                            },
+                                        )
+                                    }
+                                })
+                        };
+                        match identity_error {
+                            Some((address, error)) if address == action.address => {
+                                store
+                                    .dispatch(PeerHandshakingErrorAction { address, error }.into());
+                                return;
+                            }
+                            Some((address, error)) => {
+                                store
+                                    .dispatch(PeerHandshakingErrorAction { address, error }.into());
+                            }
+                            None => {}
+                        }
+
                         let precomputed_key = PrecomputedKey::precompute(
                             &public_key,
                             &store.state.get().config.identity.secret_key,
@@ -367,13 +519,26 @@ pub fn peer_handshaking_effects<S>(
                     ),
                     PeerStatus::Handshaking(PeerHandshaking {
                         status: PeerHandshakingStatus::AckMessageWritePending { .. },
+                        version,
                         ..
-                    }) => store.dispatch(
-                        PeerHandshakingAckMessageReadAction {
-                            address: action.address,
+                    }) => {
+                        if version.is_some() {
+                            store.dispatch(
+                                PeerHandshakingAckMessageReadAction {
+                                    address: action.address,
+                                }
+                                .into(),
+                            )
+                        } else {
+                            // We sent nack, so there's nothing more to do with the peer.
+                            store.dispatch(
+                                PeerDisconnectAction {
+                                    address: action.address,
+                                }
+                                .into(),
+                            )
                         }
-                        .into(),
-                    ),
+                    }
                     _ => {}
                 }
             }
@@ -446,14 +611,33 @@ pub fn peer_handshaking_effects<S>(
             if let Some(peer) = store.state.get().peers.get(&action.address) {
                 match &peer.status {
                     PeerStatus::Handshaking(PeerHandshaking {
-                        status: PeerHandshakingStatus::MetadataMessageReady { .. },
+                        status:
+                            PeerHandshakingStatus::MetadataMessageReady {
+                                remote_connection_message,
+                                ..
+                            },
                         ..
                     }) => {
-                        let message = AckMessage::Ack;
+                        let state = store.state.get();
+                        let remote_version = &remote_connection_message.version;
+                        let version = state
+                            .config
+                            .shell_compatibility_version
+                            .choose_compatible_version(remote_version);
+                        let message = match version.clone() {
+                            Ok(_) => AckMessage::Ack,
+                            // Peers on p2p version 0 don't understand `Nack`.
+                            Err(_) if *remote_version.p2p_version() == 0 => AckMessage::NackV0,
+                            Err(motive) => AckMessage::Nack(NackInfo::new(
+                                motive,
+                                &nack_potential_peers(state, action.address),
+                            )),
+                        };
                         store.dispatch(
                             PeerHandshakingAckMessageInitAction {
                                 address: action.address,
                                 message,
+                                version: version.ok(),
                             }
                             .into(),
                         )
@@ -551,15 +735,101 @@ pub fn peer_handshaking_effects<S>(
             if let Some(peer) = store.state.get().peers.get(&action.address) {
                 match &peer.status {
                     PeerStatus::Handshaking(PeerHandshaking {
-                        status: PeerHandshakingStatus::AckMessageReady { .. },
+                        status:
+                            PeerHandshakingStatus::AckMessageReady {
+                                remote_connection_message,
+                                ..
+                            },
+                        version,
                         ..
-                    }) => store.dispatch(PeerHandshakingFinishAction {
-                        address: action.address,
-                    }.into()),
+                    }) => {
+                        let result = version
+                            .clone()
+                            .ok_or(PeerHandshakingError::IncompatibleVersion)
+                            .and_then(|version| {
+                                PublicKey::from_bytes(&remote_connection_message.public_key)
+                                    .map(|public_key| (version, public_key))
+                                    .map_err(|err| err.into())
+                            });
+                        store.dispatch(match result {
+                            Ok((version, public_key)) => PeerHandshakingFinishAction {
+                                address: action.address,
+                                version,
+                                public_key,
+                            }
+                            .into(),
+                            Err(error) => PeerHandshakingErrorAction {
+                                address: action.address,
+                                error,
+                            }
+                            .into(),
+                        });
+                    }
+                    PeerStatus::Handshaking(PeerHandshaking {
+                        status: PeerHandshakingStatus::Nacked { motive },
+                        ..
+                    }) => {
+                        let motive = motive.clone();
+                        let potential_peers = match &action.message {
+                            AckMessage::Nack(info) => info
+                                .potential_peers_to_connect()
+                                .iter()
+                                .filter_map(|address| address.parse::<SocketAddr>().ok())
+                                .collect(),
+                            _ => vec![],
+                        };
+
+                        if !potential_peers.is_empty() {
+                            store.dispatch(
+                                PeersAddMultiAction {
+                                    addresses: potential_peers,
+                                }
+                                .into(),
+                            );
+                        }
+
+                        store.dispatch(
+                            PeerHandshakingErrorAction {
+                                address: action.address,
+                                error: PeerHandshakingError::Nacked(motive),
+                            }
+                            .into(),
+                        );
+                    }
                     _ => {}
                 }
             }
         }
+        Action::PeerHandshakingError(action) => match &action.error {
+            // peer didn't misbehave, so there's no need to graylist it.
+            PeerHandshakingError::SelfConnection
+            | PeerHandshakingError::DuplicateIdentity { .. }
+            | PeerHandshakingError::IncompatibleVersion
+            | PeerHandshakingError::Graylisted
+            | PeerHandshakingError::NotTrusted
+            // nack is a normal reply from the healthy peer, which is
+            // full, already connected to us or on another network.
+            | PeerHandshakingError::Nacked(_)
+            // impostor on the trusted host. Address is trusted, so
+            // it can't be graylisted.
+            | PeerHandshakingError::TrustedPeerIdMismatch => {
+                store.dispatch(
+                    PeerDisconnectAction {
+                        address: action.address,
+                    }
+                    .into(),
+                );
+            }
+            _ => {
+                store.dispatch(
+                    PeersGraylistAddressAction {
+                        address: action.address,
+                        reason: PeerGraylistReason::HandshakingError(action.error.clone()),
+                    }
+                    .into(),
+                );
+            }
+        },
 
         _ => {}
     }
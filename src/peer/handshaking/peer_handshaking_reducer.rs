@@ -1,5 +1,7 @@
 use redux_rs::ActionWithId;
 
+use tezos_messages::p2p::encoding::ack::{AckMessage, NackMotive};
+
 use crate::{
     action::Action,
     peer::{
@@ -16,7 +18,8 @@ use crate::{
             PeerConnectionState,
         },
         handshaking::PeerCrypto,
-        PeerStatus,
+        message::{read::PeerMessageReadState, write::PeerMessageWriteState},
+        PeerHandshaked, PeerStatus,
     },
     State,
 };
@@ -28,21 +31,23 @@ pub fn peer_handshaking_reducer(state: &mut State, action: &ActionWithId<Action>
     match &action.action {
         PeerHandshakingInit(action) => {
             if let Some(peer) = state.peers.get_mut(&action.address) {
-                match peer.status {
+                let (token, incoming) = match peer.status {
                     PeerStatus::Connecting(PeerConnectionState::Outgoing(
                         PeerConnectionOutgoingState::Success { token },
-                    ))
-                    | PeerStatus::Connecting(PeerConnectionState::Incoming(
+                    )) => (token, false),
+                    PeerStatus::Connecting(PeerConnectionState::Incoming(
                         PeerConnectionIncomingState::Success { token },
-                    )) => {
-                        peer.status = PeerStatus::Handshaking(PeerHandshaking {
-                            token,
-                            incoming: false,
-                            status: PeerHandshakingStatus::Init,
-                        });
-                    }
-                    _ => {}
-                }
+                    )) => (token, true),
+                    _ => return,
+                };
+                peer.status = PeerStatus::Handshaking(PeerHandshaking {
+                    token,
+                    incoming,
+                    status: PeerHandshakingStatus::Init,
+                    started_at: state.time,
+                    stage_started_at: state.time,
+                    version: None,
+                });
             };
         }
         PeerHandshakingConnectionMessageInit(action) => {
@@ -156,13 +161,19 @@ pub fn peer_handshaking_reducer(state: &mut State, action: &ActionWithId<Action>
 
         /////////////////// metadata exchange
         Action::PeerHandshakingMetadataMessageInit(action) => {
+            let time = state.time;
             if let Some(peer) = state.peers.get_mut(&action.address) {
                 match &mut peer.status {
-                    PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
+                    PeerStatus::Handshaking(PeerHandshaking {
+                        status,
+                        stage_started_at,
+                        ..
+                    }) => match status {
                         PeerHandshakingStatus::EncryptionReady {
                             crypto,
                             remote_connection_message,
                         } => {
+                            *stage_started_at = time;
                             *status = PeerHandshakingStatus::MetadataMessageInit {
                                 message: action.message.clone(),
                                 crypto: crypto.clone(),
@@ -278,14 +289,22 @@ pub fn peer_handshaking_reducer(state: &mut State, action: &ActionWithId<Action>
 
         /////////////////// ack exchange
         Action::PeerHandshakingAckMessageInit(action) => {
+            let time = state.time;
             if let Some(peer) = state.peers.get_mut(&action.address) {
                 match &mut peer.status {
-                    PeerStatus::Handshaking(PeerHandshaking { status, .. }) => match status {
+                    PeerStatus::Handshaking(PeerHandshaking {
+                        status,
+                        version,
+                        stage_started_at,
+                        ..
+                    }) => match status {
                         PeerHandshakingStatus::MetadataMessageReady {
                             remote_message,
                             crypto,
                             remote_connection_message,
                         } => {
+                            *stage_started_at = time;
+                            *version = action.version.clone();
                             *status = PeerHandshakingStatus::AckMessageInit {
                                 message: action.message.clone(),
                                 crypto: crypto.clone(),
@@ -390,15 +409,26 @@ pub fn peer_handshaking_reducer(state: &mut State, action: &ActionWithId<Action>
                             remote_connection_message,
                             remote_metadata_message,
                         } => {
-                            let crypto = PeerCrypto::unsplit_after_reading(
-                                crypto.clone(),
-                                local_nonce.clone(),
-                            );
-                            *status = PeerHandshakingStatus::AckMessageReady {
-                                remote_message: action.message.clone(),
-                                crypto: crypto.clone(),
-                                remote_connection_message: remote_connection_message.clone(),
-                                remote_metadata_message: remote_metadata_message.clone(),
+                            *status = match &action.message {
+                                AckMessage::Ack => {
+                                    let crypto = PeerCrypto::unsplit_after_reading(
+                                        crypto.clone(),
+                                        local_nonce.clone(),
+                                    );
+                                    PeerHandshakingStatus::AckMessageReady {
+                                        remote_message: action.message.clone(),
+                                        crypto,
+                                        remote_connection_message: remote_connection_message
+                                            .clone(),
+                                        remote_metadata_message: remote_metadata_message.clone(),
+                                    }
+                                }
+                                AckMessage::NackV0 => PeerHandshakingStatus::Nacked {
+                                    motive: NackMotive::NoMotive,
+                                },
+                                AckMessage::Nack(info) => PeerHandshakingStatus::Nacked {
+                                    motive: info.motive().clone(),
+                                },
                             }
                         }
                         _ => {}
@@ -408,6 +438,41 @@ pub fn peer_handshaking_reducer(state: &mut State, action: &ActionWithId<Action>
             }
         }
 
+        Action::PeerHandshakingFinish(action) => {
+            if let Some(peer) = state.peers.get_mut(&action.address) {
+                match &peer.status {
+                    PeerStatus::Handshaking(PeerHandshaking {
+                        token,
+                        incoming,
+                        status:
+                            PeerHandshakingStatus::AckMessageReady {
+                                crypto,
+                                remote_connection_message,
+                                remote_metadata_message,
+                                ..
+                            },
+                        ..
+                    }) => {
+                        let (read_crypto, write_crypto) = crypto.clone().split();
+                        peer.status = PeerStatus::Handshaked(PeerHandshaked {
+                            token: *token,
+                            incoming: *incoming,
+                            port: remote_connection_message.port,
+                            version: action.version.clone(),
+                            public_key: action.public_key.clone(),
+                            crypto: crypto.clone(),
+                            disable_mempool: remote_metadata_message.disable_mempool(),
+                            private_node: remote_metadata_message.private_node(),
+                            message_read: PeerMessageReadState::new(read_crypto),
+                            message_write: PeerMessageWriteState::new(write_crypto),
+                            advertise: Default::default(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+
         _ => {}
     }
 }
@@ -3,11 +3,15 @@ use crypto::crypto_box::PrecomputedKey;
 use crypto::nonce::Nonce;
 use crypto::CryptoError;
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::time::SystemTime;
 use tezos_encoding::binary_reader::BinaryReaderError;
 use tezos_encoding::binary_writer::BinaryWriterError;
 use tezos_messages::p2p::binary_message::{BinaryChunk, BinaryChunkError};
+use tezos_messages::p2p::encoding::ack::NackMotive;
 use tezos_messages::p2p::encoding::connection::ConnectionMessage;
 use tezos_messages::p2p::encoding::prelude::{AckMessage, MetadataMessage};
+use tezos_messages::p2p::encoding::version::NetworkVersion;
 
 use crate::peer::binary_message::read::peer_binary_message_read_state::PeerBinaryMessageReadState;
 use crate::peer::binary_message::write::peer_binary_message_write_state::PeerBinaryMessageWriteState;
@@ -22,6 +26,28 @@ pub enum PeerHandshakingError {
     Encoding(String),
     Decoding(String),
     Blake2b(String),
+    /// Remote peer's proof of work doesn't satisfy `config.pow_target`.
+    InsufficientProofOfWork(String),
+    /// Remote peer has the same identity as we do, so we connected to ourself.
+    SelfConnection,
+    /// We are already connected (or connecting) to the peer with the
+    /// same identity.
+    DuplicateIdentity {
+        /// Address of the connection to the peer, which we keep.
+        connected_address: SocketAddr,
+    },
+    /// Peer connected from the trusted ip, but its identity isn't
+    /// the one configured for that trusted peer.
+    TrustedPeerIdMismatch,
+    /// We don't have compatible network version with the peer.
+    IncompatibleVersion,
+    /// Peer's ip is graylisted.
+    Graylisted,
+    /// We are a private node and peer's listening address isn't one of
+    /// the trusted peers, even though it connected from the trusted ip.
+    NotTrusted,
+    /// Peer sent us nack during handshake.
+    Nacked(NackMotive),
 }
 
 impl From<CryptoError> for PeerHandshakingError {
@@ -182,7 +208,98 @@ pub enum PeerHandshakingStatus {
         remote_connection_message: ConnectionMessage,
         remote_metadata_message: MetadataMessage,
         // remote ack message has no data
-    }, // TODO Nacked, Blacklisted, ...?
+    },
+    /// Remote peer rejected us with `Nack`/`NackV0`.
+    Nacked {
+        /// `NackMotive::NoMotive` in case of `NackV0`.
+        motive: NackMotive,
+    }, // TODO Blacklisted, ...?
+}
+
+impl PeerHandshakingStatus {
+    /// Connection message received from the peer, if we got that far.
+    pub fn remote_connection_message(&self) -> Option<&ConnectionMessage> {
+        match self {
+            Self::ConnectionMessageReady { remote_message, .. } => Some(remote_message),
+            Self::EncryptionReady {
+                remote_connection_message,
+                ..
+            }
+            | Self::MetadataMessageInit {
+                remote_connection_message,
+                ..
+            }
+            | Self::MetadataMessageEncoded {
+                remote_connection_message,
+                ..
+            }
+            | Self::MetadataMessageWritePending {
+                remote_connection_message,
+                ..
+            }
+            | Self::MetadataMessageReadPending {
+                remote_connection_message,
+                ..
+            }
+            | Self::MetadataMessageReady {
+                remote_connection_message,
+                ..
+            }
+            | Self::AckMessageInit {
+                remote_connection_message,
+                ..
+            }
+            | Self::AckMessageEncoded {
+                remote_connection_message,
+                ..
+            }
+            | Self::AckMessageWritePending {
+                remote_connection_message,
+                ..
+            }
+            | Self::AckMessageReadPending {
+                remote_connection_message,
+                ..
+            }
+            | Self::AckMessageReady {
+                remote_connection_message,
+                ..
+            } => Some(remote_connection_message),
+            _ => None,
+        }
+    }
+
+    pub fn stage(&self) -> PeerHandshakingStage {
+        match self {
+            Self::Init
+            | Self::ConnectionMessageInit { .. }
+            | Self::ConnectionMessageEncoded { .. }
+            | Self::ConnectionMessageWritePending { .. }
+            | Self::ConnectionMessageReadPending { .. }
+            | Self::ConnectionMessageReady { .. }
+            | Self::EncryptionReady { .. } => PeerHandshakingStage::ConnectionMessage,
+            Self::MetadataMessageInit { .. }
+            | Self::MetadataMessageEncoded { .. }
+            | Self::MetadataMessageWritePending { .. }
+            | Self::MetadataMessageReadPending { .. }
+            | Self::MetadataMessageReady { .. } => PeerHandshakingStage::MetadataMessage,
+            Self::AckMessageInit { .. }
+            | Self::AckMessageEncoded { .. }
+            | Self::AckMessageWritePending { .. }
+            | Self::AckMessageReadPending { .. }
+            | Self::AckMessageReady { .. }
+            | Self::Nacked { .. } => PeerHandshakingStage::AckMessage,
+        }
+    }
+}
+
+/// Message exchange, which handshake consists of. Each of them is
+/// timed out separately.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerHandshakingStage {
+    ConnectionMessage,
+    MetadataMessage,
+    AckMessage,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -190,6 +307,14 @@ pub struct PeerHandshaking {
     pub token: PeerToken,
     pub status: PeerHandshakingStatus,
     pub incoming: bool,
+    pub started_at: SystemTime,
+    /// When current [PeerHandshakingStage] started.
+    pub stage_started_at: SystemTime,
+    /// Network version chosen based on the remote connection message,
+    /// when we decide whether to send `Ack` or `Nack`.
+    ///
+    /// `None` until then, or if remote peer isn't compatible with us.
+    pub version: Option<NetworkVersion>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
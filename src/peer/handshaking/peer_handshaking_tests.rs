@@ -0,0 +1,155 @@
+//! Loopback test, which handshakes two in-process nodes against each other.
+
+use redux_rs::Store;
+use std::time::{Duration, Instant};
+
+use tezos_identity::Identity;
+
+use crate::action::Action;
+use crate::config::{test_config, Config};
+use crate::event::Event;
+use crate::peer::PeerStatus;
+use crate::peers::add::multi::PeersAddMultiAction;
+use crate::service::mio_service::MioInternalEventsContainer;
+use crate::service::mock::{DnsServiceMock, RpcServiceMock, StorageServiceMock};
+use crate::service::{
+    MioService, MioServiceDefault, RandomnessServiceDefault, Service, TimeService,
+    TimeServiceDefault,
+};
+use crate::tick::TickAction;
+use crate::{effects, reducer, State};
+
+struct LoopbackService {
+    time: TimeServiceDefault,
+    randomness: RandomnessServiceDefault,
+    dns: DnsServiceMock,
+    mio: MioServiceDefault,
+    storage: StorageServiceMock,
+    rpc: RpcServiceMock,
+}
+
+impl Service for LoopbackService {
+    type Time = TimeServiceDefault;
+    type Randomness = RandomnessServiceDefault;
+    type Dns = DnsServiceMock;
+    type Mio = MioServiceDefault;
+    type Storage = StorageServiceMock;
+    type Rpc = RpcServiceMock;
+
+    fn time(&mut self) -> &mut Self::Time {
+        &mut self.time
+    }
+
+    fn randomness(&mut self) -> &mut Self::Randomness {
+        &mut self.randomness
+    }
+
+    fn dns(&mut self) -> &mut Self::Dns {
+        &mut self.dns
+    }
+
+    fn mio(&mut self) -> &mut Self::Mio {
+        &mut self.mio
+    }
+
+    fn storage(&mut self) -> &mut Self::Storage {
+        &mut self.storage
+    }
+
+    fn rpc(&mut self) -> &mut Self::Rpc {
+        &mut self.rpc
+    }
+}
+
+type LoopbackStore = Store<State, LoopbackService, Action>;
+
+/// Node listening on a port chosen by OS.
+fn node() -> LoopbackStore {
+    let mut mio = MioServiceDefault::new(([127, 0, 0, 1], 0).into());
+    mio.peer_connection_incoming_listen_start().unwrap();
+
+    let config = Config {
+        p2p_address: mio.listen_addr(),
+        identity: Identity::generate(0.0).unwrap(),
+        ..test_config()
+    };
+    let service = LoopbackService {
+        time: TimeServiceDefault::default(),
+        randomness: RandomnessServiceDefault::new(config.randomness_seed),
+        dns: DnsServiceMock::new(),
+        mio,
+        storage: StorageServiceMock::new(),
+        rpc: RpcServiceMock::new(),
+    };
+
+    let mut store = Store::new(reducer, service, State::new(config));
+    store.add_middleware(effects);
+
+    let time = store.service().time().now();
+    store.dispatch(TickAction { time }.into());
+
+    store
+}
+
+fn poll(store: &mut LoopbackStore, events: &mut MioInternalEventsContainer) {
+    store
+        .service()
+        .mio()
+        .wait_for_events(events, Some(Duration::from_millis(10)));
+    for event in events.into_iter() {
+        match store.service().mio().transform_event(event) {
+            Event::P2pServer(p2p_server_event) => store.dispatch(p2p_server_event.into()),
+            Event::P2pPeer(p2p_peer_event) => store.dispatch(p2p_peer_event.into()),
+            Event::Wakeup(wakeup_event) => store.dispatch(wakeup_event.into()),
+            _ => {}
+        }
+    }
+}
+
+fn peer_statuses(store: &LoopbackStore) -> Vec<&PeerStatus> {
+    store
+        .state()
+        .peers
+        .values()
+        .map(|peer| &peer.status)
+        .collect()
+}
+
+/// Whether the node has exactly one peer and it is handshaked.
+fn is_handshaked(store: &LoopbackStore) -> bool {
+    matches!(peer_statuses(store).as_slice(), [PeerStatus::Handshaked(_)])
+}
+
+#[test]
+fn test_handshake_loopback() {
+    let mut outgoing = node();
+    let mut incoming = node();
+
+    let incoming_address = incoming.state().config.p2p_address;
+    outgoing.dispatch(
+        PeersAddMultiAction {
+            addresses: vec![incoming_address],
+        }
+        .into(),
+    );
+
+    let mut outgoing_events = MioInternalEventsContainer::with_capacity(64);
+    let mut incoming_events = MioInternalEventsContainer::with_capacity(64);
+    let deadline = Instant::now() + Duration::from_secs(10);
+
+    loop {
+        poll(&mut outgoing, &mut outgoing_events);
+        poll(&mut incoming, &mut incoming_events);
+
+        if is_handshaked(&outgoing) && is_handshaked(&incoming) {
+            break;
+        }
+
+        assert!(
+            Instant::now() < deadline,
+            "handshake didn't finish in time.\noutgoing: {:?}\nincoming: {:?}",
+            peer_statuses(&outgoing),
+            peer_statuses(&incoming),
+        );
+    }
+}
@@ -0,0 +1,11 @@
+mod peer_message_read_state;
+pub use peer_message_read_state::*;
+
+mod peer_message_read_actions;
+pub use peer_message_read_actions::*;
+
+mod peer_message_read_reducer;
+pub use peer_message_read_reducer::*;
+
+mod peer_message_read_effects;
+pub use peer_message_read_effects::*;
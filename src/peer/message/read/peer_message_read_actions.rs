@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+use tezos_messages::p2p::encoding::peer::PeerMessageResponse;
+
+use super::PeerMessageReadError;
+
+/// Start reading next message from the handshaked peer.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerMessageReadInitAction {
+    pub address: SocketAddr,
+}
+
+/// Message has been read and decoded.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerMessageReadSuccessAction {
+    pub address: SocketAddr,
+    pub message: PeerMessageResponse,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerMessageReadErrorAction {
+    pub address: SocketAddr,
+    pub error: PeerMessageReadError,
+}
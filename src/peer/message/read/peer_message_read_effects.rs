@@ -0,0 +1,133 @@
+use redux_rs::{ActionWithId, Store};
+use tezos_messages::p2p::binary_message::BinaryRead;
+use tezos_messages::p2p::encoding::peer::PeerMessageResponse;
+
+use crate::action::Action;
+use crate::peer::binary_message::read::peer_binary_message_read_actions::PeerBinaryMessageReadInitAction;
+use crate::peer::binary_message::read::peer_binary_message_read_state::{
+    PeerBinaryMessageReadError, PeerBinaryMessageReadState,
+};
+use crate::peer::{PeerHandshaked, PeerStatus};
+use crate::peers::graylist::{PeerGraylistReason, PeersGraylistAddressAction};
+use crate::service::Service;
+use crate::State;
+
+use super::{
+    PeerMessageReadErrorAction, PeerMessageReadInitAction, PeerMessageReadState,
+    PeerMessageReadSuccessAction,
+};
+
+pub fn peer_message_read_effects<S>(
+    store: &mut Store<State, S, Action>,
+    action: &ActionWithId<Action>,
+) where
+    S: Service,
+{
+    match &action.action {
+        Action::PeerHandshakingFinish(action) => {
+            if let Some(peer) = store.state.get().peers.get(&action.address) {
+                if let PeerStatus::Handshaked(_) = &peer.status {
+                    store.dispatch(
+                        PeerMessageReadInitAction {
+                            address: action.address,
+                        }
+                        .into(),
+                    );
+                }
+            }
+        }
+        Action::PeerMessageReadInit(action) => {
+            if let Some(peer) = store.state.get().peers.get(&action.address) {
+                match &peer.status {
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_read:
+                            PeerMessageReadState::Pending {
+                                binary_message_state: PeerBinaryMessageReadState::Init { .. },
+                            },
+                        ..
+                    }) => store.dispatch(
+                        PeerBinaryMessageReadInitAction {
+                            address: action.address,
+                        }
+                        .into(),
+                    ),
+                    _ => {}
+                }
+            }
+        }
+        Action::PeerBinaryMessageReadReady(action) => {
+            if let Some(peer) = store.state.get().peers.get(&action.address) {
+                match &peer.status {
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_read:
+                            PeerMessageReadState::Pending {
+                                binary_message_state:
+                                    PeerBinaryMessageReadState::Ready { message, .. },
+                            },
+                        ..
+                    }) => match PeerMessageResponse::from_bytes(message) {
+                        Ok(message) => store.dispatch(
+                            PeerMessageReadSuccessAction {
+                                address: action.address,
+                                message,
+                            }
+                            .into(),
+                        ),
+                        Err(err) => store.dispatch(
+                            PeerMessageReadErrorAction {
+                                address: action.address,
+                                error: err.into(),
+                            }
+                            .into(),
+                        ),
+                    },
+                    _ => {}
+                }
+            }
+        }
+        Action::PeerBinaryMessageReadError(action) => {
+            if let Some(peer) = store.state.get().peers.get(&action.address) {
+                if let PeerStatus::Handshaked(_) = &peer.status {
+                    store.dispatch(
+                        PeerMessageReadErrorAction {
+                            address: action.address,
+                            error: action.error.clone().into(),
+                        }
+                        .into(),
+                    );
+                }
+            }
+        }
+        Action::PeerChunkReadError(action) => {
+            if let Some(peer) = store.state.get().peers.get(&action.address) {
+                if let PeerStatus::Handshaked(_) = &peer.status {
+                    store.dispatch(
+                        PeerMessageReadErrorAction {
+                            address: action.address,
+                            error: PeerBinaryMessageReadError::Chunk(action.error.clone()).into(),
+                        }
+                        .into(),
+                    );
+                }
+            }
+        }
+        Action::PeerMessageReadSuccess(action) => {
+            store.dispatch(
+                PeerMessageReadInitAction {
+                    address: action.address,
+                }
+                .into(),
+            );
+        }
+        Action::PeerMessageReadError(action) => {
+            store.dispatch(
+                PeersGraylistAddressAction {
+                    address: action.address,
+                    reason: PeerGraylistReason::MessageReadError(action.error.clone()),
+                }
+                .into(),
+            );
+        }
+        _ => {}
+    }
+}
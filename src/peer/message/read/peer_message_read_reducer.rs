@@ -0,0 +1,39 @@
+use redux_rs::ActionWithId;
+
+use crate::action::Action;
+use crate::peer::binary_message::read::peer_binary_message_read_state::PeerBinaryMessageReadState;
+use crate::peer::{PeerHandshaked, PeerStatus};
+use crate::State;
+
+use super::PeerMessageReadState;
+
+pub fn peer_message_read_reducer(state: &mut State, action: &ActionWithId<Action>) {
+    match &action.action {
+        Action::PeerMessageReadSuccess(action) => {
+            if let Some(peer) = state.peers.get_mut(&action.address) {
+                if let PeerStatus::Handshaked(PeerHandshaked { message_read, .. }) =
+                    &mut peer.status
+                {
+                    if let PeerMessageReadState::Pending {
+                        binary_message_state: PeerBinaryMessageReadState::Ready { crypto, .. },
+                    } = message_read
+                    {
+                        *message_read = PeerMessageReadState::new(crypto.clone());
+                    }
+                }
+            }
+        }
+        Action::PeerMessageReadError(action) => {
+            if let Some(peer) = state.peers.get_mut(&action.address) {
+                if let PeerStatus::Handshaked(PeerHandshaked { message_read, .. }) =
+                    &mut peer.status
+                {
+                    *message_read = PeerMessageReadState::Error {
+                        error: action.error.clone(),
+                    };
+                }
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+use tezos_encoding::binary_reader::BinaryReaderError;
+
+use crate::peer::binary_message::read::peer_binary_message_read_state::{
+    PeerBinaryMessageReadError, PeerBinaryMessageReadState,
+};
+use crate::peer::chunk::read::peer_chunk_read_state::ReadCrypto;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum PeerMessageReadError {
+    BinaryMessage(PeerBinaryMessageReadError),
+    Decode(String),
+}
+
+impl From<PeerBinaryMessageReadError> for PeerMessageReadError {
+    fn from(error: PeerBinaryMessageReadError) -> Self {
+        Self::BinaryMessage(error)
+    }
+}
+
+impl From<BinaryReaderError> for PeerMessageReadError {
+    fn from(error: BinaryReaderError) -> Self {
+        Self::Decode(error.to_string())
+    }
+}
+
+/// State of reading incoming messages from the handshaked peer.
+///
+/// Messages are read one after another. Once message is read and
+/// decoded, binary message state is reset to `Init` for the next one.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum PeerMessageReadState {
+    Pending {
+        binary_message_state: PeerBinaryMessageReadState,
+    },
+    Error {
+        error: PeerMessageReadError,
+    },
+}
+
+impl PeerMessageReadState {
+    pub fn new(crypto: ReadCrypto) -> Self {
+        Self::Pending {
+            binary_message_state: PeerBinaryMessageReadState::Init { crypto },
+        }
+    }
+}
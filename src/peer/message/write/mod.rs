@@ -0,0 +1,11 @@
+mod peer_message_write_state;
+pub use peer_message_write_state::*;
+
+mod peer_message_write_actions;
+pub use peer_message_write_actions::*;
+
+mod peer_message_write_reducer;
+pub use peer_message_write_reducer::*;
+
+mod peer_message_write_effects;
+pub use peer_message_write_effects::*;
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+use tezos_messages::p2p::encoding::peer::PeerMessageResponse;
+
+use super::PeerMessageWriteError;
+
+/// Queue message to be written to the handshaked peer.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerMessageWriteInitAction {
+    pub address: SocketAddr,
+    pub message: PeerMessageResponse,
+}
+
+/// Start writing next queued message, if we aren't writing one already.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerMessageWriteNextAction {
+    pub address: SocketAddr,
+}
+
+/// Message at the front of the queue has been written.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerMessageWriteSuccessAction {
+    pub address: SocketAddr,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerMessageWriteErrorAction {
+    pub address: SocketAddr,
+    pub error: PeerMessageWriteError,
+}
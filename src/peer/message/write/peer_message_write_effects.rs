@@ -0,0 +1,134 @@
+use redux_rs::{ActionWithId, Store};
+use tezos_messages::p2p::binary_message::BinaryWrite;
+
+use crate::action::Action;
+use crate::peer::binary_message::write::peer_binary_message_write_actions::PeerBinaryMessageWriteSetContentAction;
+use crate::peer::binary_message::write::peer_binary_message_write_state::{
+    PeerBinaryMessageWriteError, PeerBinaryMessageWriteState,
+};
+use crate::peer::disconnection::PeerDisconnectAction;
+use crate::peer::{PeerHandshaked, PeerStatus};
+use crate::service::Service;
+use crate::State;
+
+use super::{
+    PeerMessageWriteErrorAction, PeerMessageWriteNextAction, PeerMessageWriteState,
+    PeerMessageWriteSuccessAction,
+};
+
+pub fn peer_message_write_effects<S>(
+    store: &mut Store<State, S, Action>,
+    action: &ActionWithId<Action>,
+) where
+    S: Service,
+{
+    match &action.action {
+        Action::PeerMessageWriteInit(action) => {
+            store.dispatch(
+                PeerMessageWriteNextAction {
+                    address: action.address,
+                }
+                .into(),
+            );
+        }
+        Action::PeerMessageWriteNext(action) => {
+            if let Some(peer) = store.state.get().peers.get(&action.address) {
+                match &peer.status {
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_write:
+                            PeerMessageWriteState {
+                                queue,
+                                current: PeerBinaryMessageWriteState::Init { .. },
+                            },
+                        ..
+                    }) => {
+                        let message = match queue.front() {
+                            Some(v) => v,
+                            None => return,
+                        };
+                        match message.as_bytes() {
+                            Ok(message) => store.dispatch(
+                                PeerBinaryMessageWriteSetContentAction {
+                                    address: action.address,
+                                    message,
+                                }
+                                .into(),
+                            ),
+                            Err(err) => store.dispatch(
+                                PeerMessageWriteErrorAction {
+                                    address: action.address,
+                                    error: err.into(),
+                                }
+                                .into(),
+                            ),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Action::PeerBinaryMessageWriteReady(action) => {
+            if let Some(peer) = store.state.get().peers.get(&action.address) {
+                match &peer.status {
+                    PeerStatus::Handshaked(PeerHandshaked {
+                        message_write:
+                            PeerMessageWriteState {
+                                current: PeerBinaryMessageWriteState::Ready { .. },
+                                ..
+                            },
+                        ..
+                    }) => store.dispatch(
+                        PeerMessageWriteSuccessAction {
+                            address: action.address,
+                        }
+                        .into(),
+                    ),
+                    _ => {}
+                }
+            }
+        }
+        Action::PeerBinaryMessageWriteError(action) => {
+            if let Some(peer) = store.state.get().peers.get(&action.address) {
+                if let PeerStatus::Handshaked(_) = &peer.status {
+                    store.dispatch(
+                        PeerMessageWriteErrorAction {
+                            address: action.address,
+                            error: action.error.clone().into(),
+                        }
+                        .into(),
+                    );
+                }
+            }
+        }
+        Action::PeerChunkWriteError(action) => {
+            if let Some(peer) = store.state.get().peers.get(&action.address) {
+                if let PeerStatus::Handshaked(_) = &peer.status {
+                    store.dispatch(
+                        PeerMessageWriteErrorAction {
+                            address: action.address,
+                            error: PeerBinaryMessageWriteError::from(action.error.clone()).into(),
+                        }
+                        .into(),
+                    );
+                }
+            }
+        }
+        Action::PeerMessageWriteSuccess(action) => {
+            store.dispatch(
+                PeerMessageWriteNextAction {
+                    address: action.address,
+                }
+                .into(),
+            );
+        }
+        Action::PeerMessageWriteError(action) => {
+            store.dispatch(
+                PeerDisconnectAction {
+                    address: action.address,
+                }
+                .into(),
+            );
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,39 @@
+use redux_rs::ActionWithId;
+
+use crate::action::Action;
+use crate::peer::binary_message::write::peer_binary_message_write_state::PeerBinaryMessageWriteState;
+use crate::peer::{PeerHandshaked, PeerStatus};
+use crate::State;
+
+use super::PeerMessageWriteState;
+
+pub fn peer_message_write_reducer(state: &mut State, action: &ActionWithId<Action>) {
+    match &action.action {
+        Action::PeerMessageWriteInit(action) => {
+            if let Some(peer) = state.peers.get_mut(&action.address) {
+                if let PeerStatus::Handshaked(PeerHandshaked { message_write, .. }) =
+                    &mut peer.status
+                {
+                    message_write.queue.push_back(action.message.clone());
+                }
+            }
+        }
+        Action::PeerMessageWriteSuccess(action) => {
+            if let Some(peer) = state.peers.get_mut(&action.address) {
+                if let PeerStatus::Handshaked(PeerHandshaked {
+                    message_write: PeerMessageWriteState { queue, current },
+                    ..
+                }) = &mut peer.status
+                {
+                    if let PeerBinaryMessageWriteState::Ready { crypto } = current {
+                        *current = PeerBinaryMessageWriteState::Init {
+                            crypto: crypto.clone(),
+                        };
+                        queue.pop_front();
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
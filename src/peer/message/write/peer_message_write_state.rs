@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use tezos_encoding::binary_writer::BinaryWriterError;
+use tezos_messages::p2p::encoding::peer::PeerMessageResponse;
+
+use crate::peer::binary_message::write::peer_binary_message_write_state::{
+    PeerBinaryMessageWriteError, PeerBinaryMessageWriteState,
+};
+use crate::peer::chunk::write::peer_chunk_write_state::WriteCrypto;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum PeerMessageWriteError {
+    Encode(String),
+    BinaryMessage(PeerBinaryMessageWriteError),
+}
+
+impl From<BinaryWriterError> for PeerMessageWriteError {
+    fn from(error: BinaryWriterError) -> Self {
+        Self::Encode(error.to_string())
+    }
+}
+
+impl From<PeerBinaryMessageWriteError> for PeerMessageWriteError {
+    fn from(error: PeerBinaryMessageWriteError) -> Self {
+        Self::BinaryMessage(error)
+    }
+}
+
+/// State of writing outgoing messages to the handshaked peer.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerMessageWriteState {
+    /// Queued messages.
+    ///
+    /// Message at the front of the queue is the one being written,
+    /// unless `current` is in `Init` state. It is removed from the
+    /// queue once it has been fully written.
+    pub queue: VecDeque<PeerMessageResponse>,
+    pub current: PeerBinaryMessageWriteState,
+}
+
+impl PeerMessageWriteState {
+    pub fn new(crypto: WriteCrypto) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            current: PeerBinaryMessageWriteState::Init { crypto },
+        }
+    }
+}
@@ -3,6 +3,8 @@ pub mod chunk;
 pub mod connection;
 pub mod disconnection;
 pub mod handshaking;
+pub mod message;
+pub mod score;
 
 mod peer_token;
 pub use peer_token::*;
@@ -18,3 +20,6 @@ pub use peer_actions::*;
 
 mod peer_effects;
 pub use peer_effects::*;
+
+#[cfg(test)]
+mod peer_tests;
@@ -9,13 +9,15 @@ use crate::peer::chunk::read::peer_chunk_read_actions::{
 };
 use crate::peer::chunk::write::peer_chunk_write_state::PeerChunkWriteState;
 use crate::peer::chunk::write::{PeerChunkWriteErrorAction, PeerChunkWritePartAction};
-use crate::peer::PeerStatus;
+use crate::peer::message::read::PeerMessageReadState;
+use crate::peer::message::write::PeerMessageWriteState;
+use crate::peer::{PeerHandshaked, PeerStatus};
 use crate::service::{MioService, Service};
 use crate::State;
 
 use super::binary_message::read::peer_binary_message_read_state::PeerBinaryMessageReadState;
 use super::chunk::read::peer_chunk_read_state::PeerChunkReadState;
-use super::disconnection::PeerDisconnectedAction;
+use super::disconnection::{PeerDisconnectAction, PeerDisconnectedAction};
 use super::handshaking::PeerHandshakingStatus;
 use super::{PeerTryReadAction, PeerTryWriteAction};
 
@@ -61,6 +63,7 @@ where
 
             let peer_token = match &peer.status {
                 PeerStatus::Handshaking(s) => s.token,
+                PeerStatus::Handshaked(s) => s.token,
                 _ => return,
             };
 
@@ -87,6 +90,14 @@ where
                     },
                     _ => return,
                 },
+                PeerStatus::Handshaked(PeerHandshaked {
+                    message_write:
+                        PeerMessageWriteState {
+                            current: PeerBinaryMessageWriteState::Pending { chunk, .. },
+                            ..
+                        },
+                    ..
+                }) => &chunk.state,
                 _ => return,
             };
 
@@ -96,16 +107,13 @@ where
             } = chunk_state
             {
                 match peer_stream.write(&chunk.raw()[*prev_written..]) {
-                    Ok(written) if written > 0 => {
-                        eprintln!("<<< {}", hex::encode(&chunk.raw()[*prev_written..written]));
-                        store.dispatch(
-                            PeerChunkWritePartAction {
-                                address: action.address,
-                                written,
-                            }
-                            .into(),
-                        )
-                    }
+                    Ok(written) if written > 0 => store.dispatch(
+                        PeerChunkWritePartAction {
+                            address: action.address,
+                            written,
+                        }
+                        .into(),
+                    ),
                     Ok(_) => {}
                     Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
                     Err(err) => store.dispatch(
@@ -126,6 +134,7 @@ where
 
             let peer_token = match &peer.status {
                 PeerStatus::Handshaking(s) => s.token,
+                PeerStatus::Handshaked(s) => s.token,
                 _ => return,
             };
 
@@ -153,6 +162,17 @@ where
                     },
                     _ => return,
                 },
+                PeerStatus::Handshaked(PeerHandshaked {
+                    message_read:
+                        PeerMessageReadState::Pending {
+                            binary_message_state,
+                        },
+                    ..
+                }) => match binary_message_state {
+                    PeerBinaryMessageReadState::PendingFirstChunk { chunk, .. }
+                    | PeerBinaryMessageReadState::Pending { chunk, .. } => &chunk.state,
+                    _ => return,
+                },
                 _ => return,
             };
 
@@ -167,7 +187,6 @@ where
             let mut buff = vec![0; bytes_to_read];
             match peer_stream.read(&mut buff) {
                 Ok(bytes) if bytes > 0 => {
-                    eprintln!(">>> {}", hex::encode(&buff));
                     store.dispatch(
                         PeerChunkReadPartAction {
                             address: action.address,
@@ -176,7 +195,13 @@ where
                         .into(),
                     );
                 }
-                Ok(_) => todo!("handle eof"),
+                // peer closed the connection.
+                Ok(_) => store.dispatch(
+                    PeerDisconnectAction {
+                        address: action.address,
+                    }
+                    .into(),
+                ),
                 Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => (),
                 Err(err) => store.dispatch(
                     PeerChunkReadErrorAction {
@@ -4,22 +4,38 @@ use serde::{Deserialize, Serialize};
 use crypto::crypto_box::PublicKey;
 use tezos_messages::p2p::encoding::version::NetworkVersion;
 
+use crate::peers::advertise::PeerAdvertiseState;
 use crate::Port;
 
-use super::{
-    connection::PeerConnectionState, disconnection::PeerDisconnecting,
-    handshaking::PeerHandshaking, PeerCrypto, PeerToken,
-};
+use super::connection::outgoing::PeerConnectionAttempts;
+use super::connection::PeerConnectionState;
+use super::disconnection::PeerDisconnecting;
+use super::handshaking::{PeerCrypto, PeerHandshaking};
+use super::message::read::PeerMessageReadState;
+use super::message::write::PeerMessageWriteState;
+use super::PeerToken;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PeerHandshaked {
     pub token: PeerToken,
+    /// Whether connection was initiated by the peer.
+    pub incoming: bool,
     pub port: Port,
+    /// Network version negotiated with the peer.
     pub version: NetworkVersion,
     pub public_key: PublicKey,
+    /// Encryption data negotiated during handshake.
+    ///
+    /// Nonces here are the ones handshake finished with. Up to date
+    /// read/write halves are owned by `message_read`/`message_write`.
     pub crypto: PeerCrypto,
     pub disable_mempool: bool,
     pub private_node: bool,
+
+    pub message_read: PeerMessageReadState,
+    pub message_write: PeerMessageWriteState,
+
+    pub advertise: PeerAdvertiseState,
 }
 
 #[derive(From, Serialize, Deserialize, Debug, Clone)]
@@ -38,6 +54,7 @@ pub enum PeerStatus {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Peer {
     pub status: PeerStatus,
+    pub connection_attempts: PeerConnectionAttempts,
 }
 
 impl Peer {
@@ -0,0 +1,647 @@
+//! Peer connection flows driven with `store.dispatch` over mocked services.
+
+use std::net::SocketAddr;
+use std::time::{Duration, SystemTime};
+
+use tezos_identity::Identity;
+
+use crate::config::{test_config, Config, TezosNetwork, TrustedPeer};
+use crate::event::P2pServerEvent;
+use crate::io_error_kind::IOErrorKind;
+use crate::peer::connection::incoming::PeerConnectionIncomingState;
+use crate::peer::connection::outgoing::{
+    PeerConnectionAttempts, PeerConnectionOutgoingState, PEER_CONNECTION_BACKOFF_EXP_MAX,
+    PEER_TRUSTED_CONNECTION_BACKOFF_EXP_MAX,
+};
+use crate::peer::connection::PeerConnectionState;
+use crate::peer::disconnection::PeerDisconnectAction;
+use crate::peer::handshaking::{PeerHandshakingError, PeerHandshakingStage};
+use crate::peer::PeerStatus;
+use crate::peers::add::multi::PeersAddMultiAction;
+use crate::peers::graylist::PeerGraylistReason;
+use crate::service::mock::{
+    address, connect, dispatch_peer_event, node, node_with_config, node_with_p2p_versions,
+    peer_status, pipe, pipe_all,
+};
+use crate::service::{Service, TimeService};
+use crate::tick::TickAction;
+
+#[test]
+fn test_outgoing_connection() {
+    let mut store = node(address(1));
+    let peer_address = address(2);
+
+    store.dispatch(
+        PeersAddMultiAction {
+            addresses: vec![peer_address],
+        }
+        .into(),
+    );
+    assert!(matches!(
+        peer_status(&store, peer_address),
+        Some(PeerStatus::Connecting(PeerConnectionState::Outgoing(
+            PeerConnectionOutgoingState::Pending { .. }
+        )))
+    ));
+
+    // first writable event means connection is established.
+    dispatch_peer_event(&mut store, peer_address, false, true, false);
+    assert!(matches!(
+        peer_status(&store, peer_address),
+        Some(PeerStatus::Handshaking(_))
+    ));
+
+    // connection message must have been written.
+    let written = store
+        .service
+        .mio
+        .peer_stream(peer_address)
+        .unwrap()
+        .take_outgoing();
+    assert!(!written.is_empty());
+}
+
+#[test]
+fn test_handshake_stage_timeout() {
+    let mut store = node(address(1));
+    let peer_address = address(2);
+
+    store.dispatch(
+        PeersAddMultiAction {
+            addresses: vec![peer_address],
+        }
+        .into(),
+    );
+    dispatch_peer_event(&mut store, peer_address, false, true, false);
+    let stage = match peer_status(&store, peer_address) {
+        Some(PeerStatus::Handshaking(handshaking)) => handshaking.status.stage(),
+        status => panic!("unexpected peer status: {:?}", status),
+    };
+    assert_eq!(stage, PeerHandshakingStage::ConnectionMessage);
+
+    // peer never sends its connection message.
+    let timeout = store.state().config.peer_handshaking_timeout(stage);
+    store
+        .service
+        .time
+        .advance(timeout - Duration::from_millis(100));
+    let time = store.service().time().now();
+    store.dispatch(TickAction { time }.into());
+    assert!(matches!(
+        peer_status(&store, peer_address),
+        Some(PeerStatus::Handshaking(_))
+    ));
+
+    store.service.time.advance(Duration::from_millis(100));
+    let time = store.service().time().now();
+    store.dispatch(TickAction { time }.into());
+    assert!(!matches!(
+        peer_status(&store, peer_address),
+        Some(PeerStatus::Handshaking(_))
+    ));
+}
+
+#[test]
+fn test_outgoing_connection_refused() {
+    let mut store = node(address(1));
+    let peer_address = address(2);
+    store.service.mio.unreachable.insert(peer_address);
+
+    store.dispatch(
+        PeersAddMultiAction {
+            addresses: vec![peer_address],
+        }
+        .into(),
+    );
+    // peer is kept, but we won't reconnect to it until backoff passes.
+    assert!(matches!(
+        peer_status(&store, peer_address),
+        Some(PeerStatus::Potential)
+    ));
+    let attempts = &store.state().peers[&peer_address].connection_attempts;
+    assert_eq!(attempts.count, 1);
+    assert_eq!(attempts.last_error, Some(IOErrorKind::ConnectionRefused));
+    assert_eq!(store.service.mio.peers_len(), 0);
+
+    let backoff = store.state().config.peer_connection_backoff;
+    store.service.time.advance(backoff);
+    let time = store.service().time().now();
+    store.dispatch(TickAction { time }.into());
+
+    let attempts = &store.state().peers[&peer_address].connection_attempts;
+    assert_eq!(attempts.count, 2);
+    assert_eq!(attempts.last_attempt_at, Some(time));
+}
+
+#[test]
+fn test_incoming_connection() {
+    let mut store = node(address(1));
+    let peer_address = address(2);
+
+    store.service.mio.incoming_connection_add(peer_address);
+    store.dispatch(P2pServerEvent {}.into());
+
+    assert!(matches!(
+        peer_status(&store, peer_address),
+        Some(PeerStatus::Connecting(PeerConnectionState::Incoming(
+            PeerConnectionIncomingState::Pending { .. }
+        )))
+    ));
+}
+
+#[test]
+fn test_incoming_connection_rejected_over_threshold() {
+    let mut store = node_with_config(Config {
+        p2p_address: address(1),
+        identity: Identity::generate(0.0).unwrap(),
+        peers_connected_max: 0,
+        ..test_config()
+    });
+    let peer_address = address(2);
+
+    store.service.mio.incoming_connection_add(peer_address);
+    store.dispatch(P2pServerEvent {}.into());
+
+    assert!(peer_status(&store, peer_address).is_none());
+    assert_eq!(store.service.mio.peers_len(), 0);
+}
+
+#[test]
+fn test_incoming_connection_rejected_over_incoming_threshold() {
+    let mut store = node_with_config(Config {
+        p2p_address: address(1),
+        identity: Identity::generate(0.0).unwrap(),
+        peers_incoming_max: 1,
+        ..test_config()
+    });
+
+    store.service.mio.incoming_connection_add(address(2));
+    store.dispatch(P2pServerEvent {}.into());
+    assert!(peer_status(&store, address(2)).is_some());
+
+    store.service.mio.incoming_connection_add(address(3));
+    store.dispatch(P2pServerEvent {}.into());
+    assert!(peer_status(&store, address(3)).is_none());
+
+    // outgoing connections aren't limited by it.
+    store.dispatch(
+        PeersAddMultiAction {
+            addresses: vec![address(4)],
+        }
+        .into(),
+    );
+    assert!(matches!(
+        peer_status(&store, address(4)),
+        Some(PeerStatus::Connecting(PeerConnectionState::Outgoing(_)))
+    ));
+}
+
+#[test]
+fn test_private_node_only_trusted_peers() {
+    let trusted_address = address(2);
+    let mut store = node_with_config(Config {
+        p2p_address: address(1),
+        identity: Identity::generate(0.0).unwrap(),
+        private_node: true,
+        trusted_peers: vec![TrustedPeer {
+            address: trusted_address,
+            peer_id: None,
+        }],
+        ..test_config()
+    });
+
+    store.dispatch(
+        PeersAddMultiAction {
+            addresses: vec![address(3)],
+        }
+        .into(),
+    );
+    assert!(peer_status(&store, address(3)).is_none());
+
+    // incoming connections are matched by ip only.
+    let trusted_incoming = SocketAddr::new(trusted_address.ip(), 40000);
+    store.service.mio.incoming_connection_add(address(4));
+    store.service.mio.incoming_connection_add(trusted_incoming);
+    store.dispatch(P2pServerEvent {}.into());
+
+    assert!(peer_status(&store, address(4)).is_none());
+    assert!(peer_status(&store, trusted_incoming).is_some());
+}
+
+#[test]
+fn test_private_node_trusted_port() {
+    let (a_address, trusted_address) = (address(1), address(2));
+    // another node on the trusted host.
+    let other_address = SocketAddr::new(trusted_address.ip(), trusted_address.port() + 1);
+    let mut a = node_with_config(Config {
+        p2p_address: a_address,
+        identity: Identity::generate(0.0).unwrap(),
+        private_node: true,
+        trusted_peers: vec![TrustedPeer {
+            address: trusted_address,
+            peer_id: None,
+        }],
+        ..test_config()
+    });
+    let mut other = node(other_address);
+    let mut trusted = node(trusted_address);
+
+    // accepted by ip, but dropped once it tells us its listening port.
+    connect(&mut other, other_address, &mut a, a_address);
+    pipe_all(&mut other, other_address, &mut a, a_address);
+    assert!(peer_status(&a, other_address).is_none());
+    assert!(!a.state().is_graylisted(&other_address));
+
+    // `a` already dials the trusted peer since the initial tick.
+    connect(&mut a, a_address, &mut trusted, trusted_address);
+    pipe_all(&mut a, a_address, &mut trusted, trusted_address);
+    assert!(matches!(
+        peer_status(&a, trusted_address),
+        Some(PeerStatus::Handshaked(_))
+    ));
+}
+
+#[test]
+fn test_trusted_peer_reconnect() {
+    let trusted_address = address(2);
+    let mut store = node_with_config(Config {
+        p2p_address: address(1),
+        identity: Identity::generate(0.0).unwrap(),
+        peers_connected_max: 0,
+        trusted_peers: vec![TrustedPeer {
+            address: trusted_address,
+            peer_id: None,
+        }],
+        ..test_config()
+    });
+
+    // trusted peer is dialed on tick, regardless of the thresholds.
+    let time = store.state().time;
+    store.dispatch(TickAction { time }.into());
+    assert!(matches!(
+        peer_status(&store, trusted_address),
+        Some(PeerStatus::Connecting(_))
+    ));
+
+    store.dispatch(
+        PeerDisconnectAction {
+            address: trusted_address,
+        }
+        .into(),
+    );
+    assert!(matches!(
+        peer_status(&store, trusted_address),
+        Some(PeerStatus::Connecting(_))
+    ));
+}
+
+#[test]
+fn test_trusted_peer_connected_incoming_not_dialed() {
+    let (a_address, trusted_address) = (address(1), address(2));
+    let trusted_incoming = SocketAddr::new(trusted_address.ip(), 50000);
+    let mut a = node_with_config(Config {
+        p2p_address: a_address,
+        identity: Identity::generate(0.0).unwrap(),
+        trusted_peers: vec![TrustedPeer {
+            address: trusted_address,
+            peer_id: None,
+        }],
+        ..test_config()
+    });
+    let mut trusted = node(trusted_address);
+
+    connect(&mut trusted, trusted_incoming, &mut a, a_address);
+    pipe_all(&mut trusted, trusted_incoming, &mut a, a_address);
+    assert!(matches!(
+        peer_status(&a, trusted_incoming),
+        Some(PeerStatus::Handshaked(_))
+    ));
+
+    // `a` has been dialing the trusted address since the initial tick.
+    // Once that's dropped, `a` doesn't dial it again, since it's
+    // already connected to that peer.
+    a.dispatch(
+        PeerDisconnectAction {
+            address: trusted_address,
+        }
+        .into(),
+    );
+    let time = a.service().time().now();
+    a.dispatch(TickAction { time }.into());
+    assert!(matches!(
+        peer_status(&a, trusted_address),
+        Some(PeerStatus::Potential)
+    ));
+}
+
+#[test]
+fn test_trusted_peer_id_mismatch_not_graylisted() {
+    let (a_address, trusted_address) = (address(1), address(2));
+    let mut a = node_with_config(Config {
+        p2p_address: a_address,
+        identity: Identity::generate(0.0).unwrap(),
+        trusted_peers: vec![TrustedPeer {
+            address: trusted_address,
+            peer_id: Some(Identity::generate(0.0).unwrap().peer_id),
+        }],
+        ..test_config()
+    });
+    let mut impostor = node(trusted_address);
+
+    // `a` already dials the trusted peer since the initial tick.
+    connect(&mut a, a_address, &mut impostor, trusted_address);
+    pipe_all(&mut a, a_address, &mut impostor, trusted_address);
+
+    assert!(!matches!(
+        peer_status(&a, trusted_address),
+        Some(PeerStatus::Handshaked(_))
+    ));
+    assert!(!a.state().is_graylisted(&trusted_address));
+}
+
+#[test]
+fn test_trusted_peer_backoff_capped() {
+    let time = SystemTime::UNIX_EPOCH;
+    let backoff = Duration::from_secs(10);
+    let attempts = PeerConnectionAttempts {
+        count: 10,
+        last_error: None,
+        last_attempt_at: Some(time),
+    };
+
+    assert_eq!(
+        attempts.backoff_until(backoff, PEER_TRUSTED_CONNECTION_BACKOFF_EXP_MAX),
+        Some(time + backoff * 4)
+    );
+    assert_eq!(
+        attempts.backoff_until(backoff, PEER_CONNECTION_BACKOFF_EXP_MAX),
+        Some(time + backoff * 512)
+    );
+}
+
+#[test]
+fn test_trusted_ip_incoming_exempt_once() {
+    let trusted_address = address(2);
+    let mut store = node_with_config(Config {
+        p2p_address: address(1),
+        identity: Identity::generate(0.0).unwrap(),
+        peers_connected_max: 0,
+        trusted_peers: vec![TrustedPeer {
+            address: trusted_address,
+            peer_id: None,
+        }],
+        ..test_config()
+    });
+
+    // only one connection from the trusted ip bypasses the thresholds.
+    let (incoming_1, incoming_2) = (
+        SocketAddr::new(trusted_address.ip(), 40000),
+        SocketAddr::new(trusted_address.ip(), 40001),
+    );
+    store.service.mio.incoming_connection_add(incoming_1);
+    store.dispatch(P2pServerEvent {}.into());
+    store.service.mio.incoming_connection_add(incoming_2);
+    store.dispatch(P2pServerEvent {}.into());
+
+    assert!(peer_status(&store, incoming_1).is_some());
+    assert!(peer_status(&store, incoming_2).is_none());
+}
+
+#[test]
+fn test_disconnect() {
+    let mut store = node(address(1));
+    let peer_address = address(2);
+
+    store.dispatch(
+        PeersAddMultiAction {
+            addresses: vec![peer_address],
+        }
+        .into(),
+    );
+    dispatch_peer_event(&mut store, peer_address, false, true, false);
+
+    store.dispatch(
+        PeerDisconnectAction {
+            address: peer_address,
+        }
+        .into(),
+    );
+
+    assert!(peer_status(&store, peer_address).is_none());
+    assert_eq!(store.service.mio.peers_len(), 0);
+}
+
+#[test]
+fn test_handshake() {
+    let (a_address, b_address) = (address(1), address(2));
+    let mut a = node(a_address);
+    let mut b = node(b_address);
+
+    connect(&mut a, a_address, &mut b, b_address);
+    pipe_all(&mut a, a_address, &mut b, b_address);
+
+    assert!(matches!(
+        peer_status(&a, b_address),
+        Some(PeerStatus::Handshaked(_))
+    ));
+    assert!(matches!(
+        peer_status(&b, a_address),
+        Some(PeerStatus::Handshaked(_))
+    ));
+}
+
+#[test]
+fn test_handshake_insufficient_pow() {
+    let (a_address, b_address) = (address(1), address(2));
+    let mut a = node(a_address);
+    let mut b = node_with_config(Config {
+        p2p_address: b_address,
+        identity: Identity::generate(0.0).unwrap(),
+        pow_target: 100.0,
+        ..test_config()
+    });
+
+    connect(&mut a, a_address, &mut b, b_address);
+    pipe_all(&mut a, a_address, &mut b, b_address);
+
+    assert!(peer_status(&b, a_address).is_none());
+    assert!(matches!(
+        b.state()
+            .peers_graylist
+            .get(&a_address.ip())
+            .map(|g| &g.reason),
+        Some(PeerGraylistReason::HandshakingError(
+            PeerHandshakingError::InsufficientProofOfWork(_)
+        ))
+    ));
+}
+
+#[test]
+fn test_handshake_nack_v0() {
+    let (a_address, b_address) = (address(1), address(2));
+    // `a` only speaks p2p version 0, which `b` doesn't support.
+    let mut a = node_with_p2p_versions(a_address, vec![0]);
+    let mut b = node_with_p2p_versions(b_address, vec![1]);
+
+    connect(&mut a, a_address, &mut b, b_address);
+    pipe_all(&mut a, a_address, &mut b, b_address);
+
+    assert!(peer_status(&b, a_address).is_none());
+    assert!(b.state().peers_graylist.get(&a_address.ip()).is_none());
+    // nack isn't a misbehavior, so `a` only backs off from `b`.
+    assert!(a.state().peers_graylist.is_empty());
+    assert_eq!(a.state().peers[&b_address].connection_attempts.count, 1);
+}
+
+#[test]
+fn test_handshake_nack_potential_peers() {
+    let (a_address, b_address, c_address) = (address(1), address(2), address(3));
+    let mut a = node_with_p2p_versions(a_address, vec![1]);
+    let mut b = node_with_p2p_versions(b_address, vec![2, 3]);
+    let mut c = node_with_p2p_versions(c_address, vec![2, 3]);
+
+    connect(&mut c, c_address, &mut b, b_address);
+    pipe_all(&mut c, c_address, &mut b, b_address);
+    assert!(matches!(
+        peer_status(&b, c_address),
+        Some(PeerStatus::Handshaked(_))
+    ));
+
+    connect(&mut a, a_address, &mut b, b_address);
+    pipe_all(&mut a, a_address, &mut b, b_address);
+
+    assert!(peer_status(&b, a_address).is_none());
+    assert!(a.state().peers_graylist.is_empty());
+    assert!(!matches!(
+        peer_status(&a, b_address),
+        Some(PeerStatus::Handshaked(_))
+    ));
+    // `b` advertised the peers it's connected to in the `Nack`.
+    assert!(matches!(
+        peer_status(&a, c_address),
+        Some(PeerStatus::Potential)
+    ));
+}
+
+#[test]
+fn test_handshake_self_connection() {
+    let (a_address, b_address) = (address(1), address(2));
+    let mut a = node(a_address);
+    // same node, listening on another address.
+    let mut b = node_with_config(Config {
+        p2p_address: b_address,
+        ..a.state().config.clone()
+    });
+
+    connect(&mut a, a_address, &mut b, b_address);
+    pipe_all(&mut a, a_address, &mut b, b_address);
+
+    assert!(!matches!(
+        peer_status(&a, b_address),
+        Some(PeerStatus::Handshaked(_))
+    ));
+    assert!(peer_status(&b, a_address).is_none());
+    assert!(a.state().peers_graylist.is_empty());
+    assert!(b.state().peers_graylist.is_empty());
+}
+
+#[test]
+fn test_handshake_duplicate_identity() {
+    let (a_address, b_address, c_address) = (address(1), address(2), address(3));
+    let mut a = node(a_address);
+    let mut b = node(b_address);
+    // `c` has the same identity as `b`.
+    let mut c = node_with_config(Config {
+        p2p_address: c_address,
+        ..b.state().config.clone()
+    });
+
+    connect(&mut a, a_address, &mut b, b_address);
+    pipe_all(&mut a, a_address, &mut b, b_address);
+    assert!(matches!(
+        peer_status(&a, b_address),
+        Some(PeerStatus::Handshaked(_))
+    ));
+
+    connect(&mut a, a_address, &mut c, c_address);
+    pipe_all(&mut a, a_address, &mut c, c_address);
+    assert!(matches!(
+        peer_status(&a, b_address),
+        Some(PeerStatus::Handshaked(_))
+    ));
+    assert!(!matches!(
+        peer_status(&a, c_address),
+        Some(PeerStatus::Handshaked(_))
+    ));
+    assert!(a.state().peers_graylist.is_empty());
+}
+
+#[test]
+fn test_handshake_simultaneous_connect() {
+    let (a_address, b_address) = (address(1), address(2));
+    let mut a = node(a_address);
+    let mut b = node(b_address);
+    // addresses of the outgoing connections, as seen by the other side.
+    let a_outgoing = SocketAddr::new(a_address.ip(), 50000);
+    let b_outgoing = SocketAddr::new(b_address.ip(), 50000);
+
+    connect(&mut a, a_outgoing, &mut b, b_address);
+    connect(&mut b, b_outgoing, &mut a, a_address);
+    for _ in 0..100 {
+        let moved = [
+            pipe(&mut a, a_outgoing, &mut b, b_address),
+            pipe(&mut b, b_address, &mut a, a_outgoing),
+            pipe(&mut b, b_outgoing, &mut a, a_address),
+            pipe(&mut a, a_address, &mut b, b_outgoing),
+        ];
+        if moved.iter().all(|moved| !moved) {
+            break;
+        }
+    }
+
+    // both sides keep the connection initiated by the lower peer_id.
+    let (a_kept, b_kept) = if a.state().config.identity.peer_id < b.state().config.identity.peer_id
+    {
+        (b_address, a_outgoing)
+    } else {
+        (b_outgoing, a_address)
+    };
+    let a_handshaked = a
+        .state()
+        .peers
+        .iter()
+        .filter(|(_, peer)| matches!(&peer.status, PeerStatus::Handshaked(_)))
+        .map(|(address, _)| *address)
+        .collect::<Vec<_>>();
+    let b_handshaked = b
+        .state()
+        .peers
+        .iter()
+        .filter(|(_, peer)| matches!(&peer.status, PeerStatus::Handshaked(_)))
+        .map(|(address, _)| *address)
+        .collect::<Vec<_>>();
+    assert_eq!(a_handshaked, vec![a_kept]);
+    assert_eq!(b_handshaked, vec![b_kept]);
+}
+
+#[test]
+fn test_handshake_unknown_chain_not_graylisted() {
+    let (a_address, b_address) = (address(1), address(2));
+    let mut a = node(a_address);
+    let mut b = node_with_config(Config {
+        p2p_address: b_address,
+        identity: Identity::generate(0.0).unwrap(),
+        shell_compatibility_version: TezosNetwork::Mainnet.shell_compatibility_version(),
+        ..test_config()
+    });
+
+    connect(&mut a, a_address, &mut b, b_address);
+    pipe_all(&mut a, a_address, &mut b, b_address);
+
+    assert!(!matches!(
+        peer_status(&a, b_address),
+        Some(PeerStatus::Handshaked(_))
+    ));
+    assert!(a.state().peers_graylist.is_empty());
+    assert!(b.state().peers_graylist.is_empty());
+}
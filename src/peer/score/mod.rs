@@ -0,0 +1,11 @@
+mod peer_score_state;
+pub use peer_score_state::*;
+
+mod peer_score_reducer;
+pub use peer_score_reducer::*;
+
+mod peer_score_effects;
+pub use peer_score_effects::*;
+
+#[cfg(test)]
+mod peer_score_tests;
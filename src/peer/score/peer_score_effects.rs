@@ -0,0 +1,42 @@
+use redux_rs::{ActionWithId, Store};
+
+use crate::peer::disconnection::PeerDisconnectAction;
+use crate::peers::graylist::{PeerGraylistReason, PeersGraylistAddressAction};
+use crate::service::Service;
+use crate::{action::Action, State};
+
+use super::peer_score_delta;
+
+pub fn peer_score_effects<S>(store: &mut Store<State, S, Action>, action: &ActionWithId<Action>)
+where
+    S: Service,
+{
+    let state = store.state.get();
+    // Score has already been updated by the reducer. Here we only
+    // need to know whether this action lowered it.
+    let address = match peer_score_delta(state, &action.action) {
+        Some((address, delta)) if delta < 0 => address,
+        _ => return,
+    };
+    if state.is_graylisted(&address)
+        || state.is_trusted_address(&state.peer_listen_address(address))
+    {
+        return;
+    }
+    if !state.peers.contains_key(&address) {
+        return;
+    }
+    let score = state.peer_score(&address);
+
+    if score <= state.config.peer_score_graylist_threshold {
+        store.dispatch(
+            PeersGraylistAddressAction {
+                address,
+                reason: PeerGraylistReason::LowScore(score),
+            }
+            .into(),
+        );
+    } else if score <= state.config.peer_score_disconnect_threshold {
+        store.dispatch(PeerDisconnectAction { address }.into());
+    }
+}
@@ -0,0 +1,98 @@
+use redux_rs::ActionWithId;
+use std::net::SocketAddr;
+
+use crate::peer::binary_message::read::peer_binary_message_read_state::PeerBinaryMessageReadError;
+use crate::peer::chunk::read::peer_chunk_read_state::PeerChunkReadError;
+use crate::peer::message::read::PeerMessageReadError;
+use crate::peer::PeerStatus;
+use crate::{action::Action, State};
+
+use super::*;
+
+/// Score change for the peer, caused by the action.
+pub fn peer_score_delta(state: &State, action: &Action) -> Option<(SocketAddr, PeerScore)> {
+    match action {
+        Action::PeerHandshakingFinish(action) => {
+            let peer = state.peers.get(&action.address)?;
+            match &peer.status {
+                PeerStatus::Handshaked(_) => Some((action.address, PEER_SCORE_HANDSHAKE_SUCCESS)),
+                _ => None,
+            }
+        }
+        // only useful data is rewarded. Otherwise peer could make up
+        // for any penalty by spamming us with cheap messages.
+        Action::ChainBlockHeaderReceived(action) => {
+            Some((action.address, PEER_SCORE_BLOCK_HEADER_RECEIVED))
+        }
+        Action::ChainPeerCurrentHeadUpdate(action) => {
+            let level = action.head.header.level();
+            let chain = &state.chain;
+            let prev_level = chain
+                .peers_heads
+                .get(&action.address)
+                .map_or(0, |peer_head| peer_head.head.header.level());
+            if level > chain.current_head_level().unwrap_or(0) && level > prev_level {
+                Some((action.address, PEER_SCORE_CURRENT_HEAD_AHEAD))
+            } else {
+                None
+            }
+        }
+        Action::PeerBinaryMessageReadError(action) => match &action.error {
+            PeerBinaryMessageReadError::Decode(_) => {
+                Some((action.address, PEER_SCORE_DECODE_ERROR))
+            }
+            // chunk errors are scored on `PeerChunkReadError`.
+            PeerBinaryMessageReadError::Chunk(_) => None,
+        },
+        Action::PeerMessageReadError(action) => match &action.error {
+            PeerMessageReadError::Decode(_) => Some((action.address, PEER_SCORE_DECODE_ERROR)),
+            // already scored on `PeerBinaryMessageReadError`.
+            PeerMessageReadError::BinaryMessage(_) => None,
+        },
+        Action::PeerChunkReadError(action) => match &action.error {
+            PeerChunkReadError::Crypto(_) => Some((action.address, PEER_SCORE_CRYPTO_ERROR)),
+            PeerChunkReadError::IO(_) => None,
+        },
+        Action::PeerTimeout(action) => Some((action.address, PEER_SCORE_TIMEOUT)),
+        Action::ChainBlockHeadersGetTimeout(action) => Some((action.address, PEER_SCORE_TIMEOUT)),
+        _ => None,
+    }
+}
+
+/// Remove scores of the peers we aren't connected to, starting with
+/// the ones closest to 0, until there are at most [PEERS_SCORES_MAX].
+fn peers_scores_prune(state: &mut State) {
+    while state.peers_scores.len() > PEERS_SCORES_MAX {
+        let address = state
+            .peers_scores
+            .iter()
+            .filter(|(address, _)| {
+                !state.peers.contains_key(address) && !state.is_listen_address_connected(address)
+            })
+            .min_by_key(|(_, score)| score.abs())
+            .map(|(address, _)| *address);
+        match address {
+            Some(address) => state.peers_scores.remove(&address),
+            None => return,
+        };
+    }
+}
+
+pub fn peer_score_reducer(state: &mut State, action: &ActionWithId<Action>) {
+    if let Some((address, delta)) = peer_score_delta(state, &action.action) {
+        if !state.peers.contains_key(&address) {
+            return;
+        }
+        let score = peer_score_add(state.peer_score(&address), delta);
+        let address = match state.peer_score_address(&address) {
+            Some(v) => v,
+            None => return,
+        };
+        if score == 0 {
+            state.peers_scores.remove(&address);
+        } else {
+            state.peers_scores.insert(address, score);
+            peers_scores_prune(state);
+        }
+    }
+}
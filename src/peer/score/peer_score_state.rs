@@ -0,0 +1,29 @@
+//! Peer's reputation score.
+//!
+//! Score is updated by [super::peer_score_reducer] on peer related
+//! events. Thresholds for disconnecting and graylisting the peer are
+//! set in [crate::config::Config].
+
+pub type PeerScore = i32;
+
+pub const PEER_SCORE_MIN: PeerScore = -1000;
+/// Max number of entries in [crate::State::peers_scores].
+pub const PEERS_SCORES_MAX: usize = 1000;
+pub const PEER_SCORE_MAX: PeerScore = 100;
+
+pub const PEER_SCORE_HANDSHAKE_SUCCESS: PeerScore = 10;
+/// Peer sent us the block header we requested.
+pub const PEER_SCORE_BLOCK_HEADER_RECEIVED: PeerScore = 1;
+/// Peer announced the head, which is ahead of us and of the head it
+/// announced before.
+pub const PEER_SCORE_CURRENT_HEAD_AHEAD: PeerScore = 1;
+pub const PEER_SCORE_TIMEOUT: PeerScore = -20;
+pub const PEER_SCORE_DECODE_ERROR: PeerScore = -50;
+pub const PEER_SCORE_CRYPTO_ERROR: PeerScore = -100;
+
+pub fn peer_score_add(score: PeerScore, delta: PeerScore) -> PeerScore {
+    score
+        .saturating_add(delta)
+        .max(PEER_SCORE_MIN)
+        .min(PEER_SCORE_MAX)
+}
@@ -0,0 +1,134 @@
+//! Peer score changes driven with `store.dispatch` over mocked services.
+
+use std::net::SocketAddr;
+
+use tezos_identity::Identity;
+use tezos_messages::p2p::encoding::current_head::GetCurrentHeadMessage;
+use tezos_messages::p2p::encoding::peer::PeerMessage;
+
+use crate::chain::block_headers::ChainBlockHeadersGetTimeoutAction;
+use crate::config::{test_config, Config};
+use crate::event::P2pServerEvent;
+use crate::peer::handshaking::PeerHandshakingStage;
+use crate::peer::message::read::PeerMessageReadSuccessAction;
+use crate::peer::score::{PeerScore, PEER_SCORE_MIN};
+use crate::peer::PeerStatus;
+use crate::peers::graylist::PeerGraylistReason;
+use crate::service::mock::{
+    address, connect, dispatch_peer_event, node, node_with_config, peer_status, pipe_all, MockStore,
+};
+use crate::service::{Service, TimeService};
+use crate::tick::TickAction;
+
+/// Lower peer's score by timing out its block headers requests, until
+/// `until` is true or peer is removed.
+fn peer_score_lower_until<F>(store: &mut MockStore, address: SocketAddr, until: F) -> PeerScore
+where
+    F: Fn(&MockStore) -> bool,
+{
+    while store.state().peers.contains_key(&address) && !until(store) {
+        store.dispatch(ChainBlockHeadersGetTimeoutAction { address }.into());
+    }
+    store.state().peer_score(&address)
+}
+
+#[test]
+fn test_peer_score_disconnect() {
+    let (a_address, b_address) = (address(1), address(2));
+    let mut a = node(a_address);
+    let mut b = node(b_address);
+
+    connect(&mut a, a_address, &mut b, b_address);
+    pipe_all(&mut a, a_address, &mut b, b_address);
+    assert!(a.state().peer_score(&b_address) > 0);
+
+    let score = peer_score_lower_until(&mut a, b_address, |_| false);
+    let config = &a.state().config;
+    assert!(score <= config.peer_score_disconnect_threshold);
+    assert!(score > config.peer_score_graylist_threshold);
+    assert!(!a.state().is_graylisted(&b_address));
+
+    // score outlives the peer.
+    assert!(peer_status(&a, b_address).is_none());
+    assert_eq!(a.state().peers_scores.get(&b_address), Some(&score));
+}
+
+#[test]
+fn test_peer_score_incoming_timeout_not_kept() {
+    let mut store = node(address(1));
+    // random port of the incoming connection.
+    let peer_address = SocketAddr::new(address(2).ip(), 50123);
+
+    store.service.mio.incoming_connection_add(peer_address);
+    store.dispatch(P2pServerEvent {}.into());
+    dispatch_peer_event(&mut store, peer_address, false, true, false);
+
+    // peer never sends its connection message, so its listening
+    // port stays unknown.
+    let timeout = store
+        .state()
+        .config
+        .peer_handshaking_timeout(PeerHandshakingStage::ConnectionMessage);
+    store.service.time.advance(timeout);
+    let time = store.service().time().now();
+    store.dispatch(TickAction { time }.into());
+
+    assert!(!matches!(
+        peer_status(&store, peer_address),
+        Some(PeerStatus::Handshaking(_))
+    ));
+    assert!(store.state().peers_scores.is_empty());
+}
+
+#[test]
+fn test_peer_score_cheap_messages_not_rewarded() {
+    let (a_address, b_address) = (address(1), address(2));
+    let mut a = node(a_address);
+    let mut b = node(b_address);
+
+    connect(&mut a, a_address, &mut b, b_address);
+    pipe_all(&mut a, a_address, &mut b, b_address);
+    let score = a.state().peer_score(&b_address);
+
+    let chain_id = a.state().config.chain_id.clone();
+    for _ in 0..10 {
+        for message in vec![
+            PeerMessage::Bootstrap,
+            PeerMessage::GetCurrentHead(GetCurrentHeadMessage::new(chain_id.clone())),
+        ] {
+            a.dispatch(
+                PeerMessageReadSuccessAction {
+                    address: b_address,
+                    message: message.into(),
+                }
+                .into(),
+            );
+        }
+    }
+    assert_eq!(a.state().peer_score(&b_address), score);
+}
+
+#[test]
+fn test_peer_score_graylist() {
+    let (a_address, b_address) = (address(1), address(2));
+    let mut a = node_with_config(Config {
+        p2p_address: a_address,
+        identity: Identity::generate(0.0).unwrap(),
+        peer_score_disconnect_threshold: PEER_SCORE_MIN,
+        ..test_config()
+    });
+    let mut b = node(b_address);
+
+    connect(&mut a, a_address, &mut b, b_address);
+    pipe_all(&mut a, a_address, &mut b, b_address);
+
+    let score = peer_score_lower_until(&mut a, b_address, |a| a.state().is_graylisted(&b_address));
+    assert!(score <= a.state().config.peer_score_graylist_threshold);
+    assert!(matches!(
+        a.state()
+            .peers_graylist
+            .get(&b_address.ip())
+            .map(|x| &x.reason),
+        Some(PeerGraylistReason::LowScore(_))
+    ));
+}
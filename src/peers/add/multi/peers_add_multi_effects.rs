@@ -4,11 +4,28 @@ use crate::peer::connection::outgoing::PeerConnectionOutgoingRandomInitAction;
 use crate::service::Service;
 use crate::{action::Action, State};
 
+use super::PeersAddMultiAction;
+
 pub fn peers_add_multi_effects<S: Service>(
     store: &mut Store<State, S, Action>,
     action: &ActionWithId<Action>,
 ) {
     match &action.action {
+        Action::Tick(_) => {
+            // trusted peers must always be in state, so that we
+            // keep connecting to them.
+            let state = store.state.get();
+            let addresses = state
+                .config
+                .trusted_peers
+                .iter()
+                .map(|trusted| trusted.address)
+                .filter(|address| !state.peers.contains_key(address))
+                .collect::<Vec<_>>();
+            if !addresses.is_empty() {
+                store.dispatch(PeersAddMultiAction { addresses }.into());
+            }
+        }
         Action::PeersAddMulti(_) => {
             store.dispatch(PeerConnectionOutgoingRandomInitAction {}.into());
         }
@@ -1,4 +1,5 @@
 use redux_rs::ActionWithId;
+use std::collections::btree_map::Entry;
 
 use crate::{
     action::Action,
@@ -9,11 +10,29 @@ use crate::{
 pub fn peers_add_multi_reducer(state: &mut State, action: &ActionWithId<Action>) {
     match &action.action {
         Action::PeersAddMulti(action) => {
-            // TODO: check peers thresholds.
+            let mut potential_len = state.peers_potential_len();
+            let potential_max = state.config.peers_potential_max;
+            let private_node = state.config.private_node;
+
             for address in &action.addresses {
-                state.peers.entry(*address).or_insert_with(|| Peer {
-                    status: PeerStatus::Potential,
-                });
+                // trusted peers aren't limited by `peers_potential_max`.
+                let is_trusted = state.is_trusted_address(address);
+                if potential_len >= potential_max && !is_trusted {
+                    continue;
+                }
+                if state.is_graylisted(address) {
+                    continue;
+                }
+                if private_node && !is_trusted {
+                    continue;
+                }
+                if let Entry::Vacant(entry) = state.peers.entry(*address) {
+                    entry.insert(Peer {
+                        status: PeerStatus::Potential,
+                        connection_attempts: Default::default(),
+                    });
+                    potential_len += 1;
+                }
             }
         }
         _ => {}
@@ -9,14 +9,18 @@ use crate::{
 pub fn peers_add_reducer(state: &mut State, action: &ActionWithId<Action>) {
     match &action.action {
         Action::PeersAddIncomingPeer(action) => {
-            // TODO: check peers thresholds.
+            // peer thresholds are checked before accepting incoming
+            // connection, in `peer_connection_incoming_accept_effects`.
+            let time = state.time;
             state.peers.entry(action.address).or_insert_with(|| Peer {
                 status: PeerStatus::Connecting(
                     PeerConnectionIncomingState::Pending {
                         token: action.token,
+                        started_at: time,
                     }
                     .into(),
                 ),
+                connection_attempts: Default::default(),
             });
         }
         _ => {}
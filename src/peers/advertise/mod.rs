@@ -0,0 +1,14 @@
+mod peers_advertise_state;
+pub use peers_advertise_state::*;
+
+mod peers_advertise_actions;
+pub use peers_advertise_actions::*;
+
+mod peers_advertise_reducer;
+pub use peers_advertise_reducer::*;
+
+mod peers_advertise_effects;
+pub use peers_advertise_effects::*;
+
+#[cfg(test)]
+mod peers_advertise_tests;
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+/// Ask the handshaked peer for its known peers.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeersAdvertiseBootstrapSendAction {
+    pub address: SocketAddr,
+}
+
+/// Answer peer's `Bootstrap` message with our known peers.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeersAdvertiseSendAction {
+    pub address: SocketAddr,
+    pub addresses: Vec<SocketAddr>,
+}
+
+/// Peer advertised its known peers to us.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeersAdvertiseReceivedAction {
+    pub address: SocketAddr,
+    pub addresses: Vec<SocketAddr>,
+}
@@ -0,0 +1,188 @@
+use redux_rs::{ActionWithId, Store};
+use std::net::SocketAddr;
+
+use tezos_messages::p2p::encoding::advertise::AdvertiseMessage;
+use tezos_messages::p2p::encoding::peer::PeerMessage;
+
+use crate::peer::message::write::PeerMessageWriteInitAction;
+use crate::peer::{PeerHandshaked, PeerStatus};
+use crate::peers::add::multi::PeersAddMultiAction;
+use crate::service::{RandomnessService, Service};
+use crate::{action::Action, State};
+
+use super::{
+    PeerAdvertiseState, PeersAdvertiseBootstrapSendAction, PeersAdvertiseReceivedAction,
+    PeersAdvertiseSendAction, PEERS_ADVERTISE_MAX_LEN,
+};
+
+fn peer_advertise_state<'a>(
+    state: &'a State,
+    address: &SocketAddr,
+) -> Option<&'a PeerAdvertiseState> {
+    match &state.peers.get(address)?.status {
+        PeerStatus::Handshaked(PeerHandshaked { advertise, .. }) => Some(advertise),
+        _ => None,
+    }
+}
+
+/// Addresses of the peers which we can advertise to the peer with
+/// address `to`.
+///
+/// Only handshaked (known to be good) peers are advertised, with
+/// their listening ports. At most [PEERS_ADVERTISE_MAX_LEN] of them
+/// are sampled from these, so that we don't always advertise the same
+/// (lowest) addresses.
+fn peers_advertise_addresses(state: &State, to: SocketAddr) -> Vec<SocketAddr> {
+    state
+        .peers
+        .iter()
+        .filter(|(address, _)| **address != to)
+        .filter(|(address, _)| !state.is_graylisted(address))
+        .filter_map(|(address, peer)| match &peer.status {
+            PeerStatus::Handshaked(handshaked) if !handshaked.private_node => {
+                Some(SocketAddr::new(address.ip(), handshaked.port))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn peers_advertise_effects<S>(
+    store: &mut Store<State, S, Action>,
+    action: &ActionWithId<Action>,
+) where
+    S: Service,
+{
+    // private node neither advertises itself or other peers, nor
+    // accepts advertised peers.
+    if store.state.get().config.private_node {
+        return;
+    }
+
+    match &action.action {
+        Action::PeerHandshakingFinish(action) => {
+            if peer_advertise_state(store.state.get(), &action.address).is_some() {
+                store.dispatch(
+                    PeersAdvertiseBootstrapSendAction {
+                        address: action.address,
+                    }
+                    .into(),
+                );
+            }
+        }
+        Action::Tick(_) => {
+            // ask connected peers for more peers, if we are running low.
+            let state = store.state.get();
+            if state.peers_potential_len() >= state.config.peers_potential_min {
+                return;
+            }
+            let interval = state.config.peers_advertise_interval;
+            let addresses = state
+                .peers
+                .iter()
+                .filter_map(|(address, peer)| match &peer.status {
+                    PeerStatus::Handshaked(PeerHandshaked { advertise, .. })
+                        if PeerAdvertiseState::is_elapsed(
+                            advertise.bootstrap_sent_at,
+                            state.time,
+                            interval,
+                        ) =>
+                    {
+                        Some(*address)
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            for address in addresses {
+                store.dispatch(PeersAdvertiseBootstrapSendAction { address }.into());
+            }
+        }
+        Action::PeersAdvertiseBootstrapSend(action) => {
+            store.dispatch(
+                PeerMessageWriteInitAction {
+                    address: action.address,
+                    message: PeerMessage::Bootstrap.into(),
+                }
+                .into(),
+            );
+        }
+        Action::PeerMessageReadSuccess(action) => {
+            let state = store.state.get();
+            let advertise = match peer_advertise_state(state, &action.address) {
+                Some(v) => v,
+                None => return,
+            };
+            let interval = state.config.peers_advertise_interval;
+
+            match action.message.message() {
+                PeerMessage::Bootstrap => {
+                    if !PeerAdvertiseState::is_elapsed(
+                        advertise.advertise_sent_at,
+                        state.time,
+                        interval,
+                    ) {
+                        return;
+                    }
+                    let addresses = peers_advertise_addresses(state, action.address);
+                    if addresses.is_empty() {
+                        return;
+                    }
+                    let addresses = store
+                        .service
+                        .randomness()
+                        .choose_peers(&addresses, PEERS_ADVERTISE_MAX_LEN);
+                    store.dispatch(
+                        PeersAdvertiseSendAction {
+                            address: action.address,
+                            addresses,
+                        }
+                        .into(),
+                    );
+                }
+                PeerMessage::Advertise(message) => {
+                    if !PeerAdvertiseState::is_elapsed(
+                        advertise.advertise_received_at,
+                        state.time,
+                        interval,
+                    ) {
+                        return;
+                    }
+                    let addresses = message
+                        .id()
+                        .iter()
+                        .take(PEERS_ADVERTISE_MAX_LEN)
+                        .filter_map(|address| address.parse().ok())
+                        .collect();
+                    store.dispatch(
+                        PeersAdvertiseReceivedAction {
+                            address: action.address,
+                            addresses,
+                        }
+                        .into(),
+                    );
+                }
+                _ => {}
+            }
+        }
+        Action::PeersAdvertiseSend(action) => {
+            store.dispatch(
+                PeerMessageWriteInitAction {
+                    address: action.address,
+                    message: PeerMessage::Advertise(AdvertiseMessage::new(&action.addresses))
+                        .into(),
+                }
+                .into(),
+            );
+        }
+        Action::PeersAdvertiseReceived(action) => {
+            store.dispatch(
+                PeersAddMultiAction {
+                    addresses: action.addresses.clone(),
+                }
+                .into(),
+            );
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,39 @@
+use redux_rs::ActionWithId;
+use std::net::SocketAddr;
+
+use crate::peer::{PeerHandshaked, PeerStatus};
+use crate::{action::Action, State};
+
+use super::PeerAdvertiseState;
+
+fn peer_advertise_state_mut<'a>(
+    state: &'a mut State,
+    address: &SocketAddr,
+) -> Option<&'a mut PeerAdvertiseState> {
+    match &mut state.peers.get_mut(address)?.status {
+        PeerStatus::Handshaked(PeerHandshaked { advertise, .. }) => Some(advertise),
+        _ => None,
+    }
+}
+
+pub fn peers_advertise_reducer(state: &mut State, action: &ActionWithId<Action>) {
+    let time = state.time;
+    match &action.action {
+        Action::PeersAdvertiseBootstrapSend(action) => {
+            if let Some(advertise) = peer_advertise_state_mut(state, &action.address) {
+                advertise.bootstrap_sent_at = Some(time);
+            }
+        }
+        Action::PeersAdvertiseSend(action) => {
+            if let Some(advertise) = peer_advertise_state_mut(state, &action.address) {
+                advertise.advertise_sent_at = Some(time);
+            }
+        }
+        Action::PeersAdvertiseReceived(action) => {
+            if let Some(advertise) = peer_advertise_state_mut(state, &action.address) {
+                advertise.advertise_received_at = Some(time);
+            }
+        }
+        _ => {}
+    }
+}
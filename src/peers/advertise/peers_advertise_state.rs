@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime};
+
+/// Max number of addresses in the `AdvertiseMessage`.
+pub const PEERS_ADVERTISE_MAX_LEN: usize = 100;
+
+/// Peer exchange (`Bootstrap`/`Advertise` messages) with the handshaked peer.
+///
+/// Used to rate limit the exchange in both directions.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct PeerAdvertiseState {
+    /// Last time we sent `Bootstrap` message to the peer.
+    pub bootstrap_sent_at: Option<SystemTime>,
+    /// Last time we answered peer's `Bootstrap` with `Advertise`.
+    pub advertise_sent_at: Option<SystemTime>,
+    /// Last time we accepted `Advertise` message from the peer.
+    pub advertise_received_at: Option<SystemTime>,
+}
+
+impl PeerAdvertiseState {
+    /// Whether `interval` has passed since `time`.
+    pub fn is_elapsed(time: Option<SystemTime>, now: SystemTime, interval: Duration) -> bool {
+        time.map_or(true, |time| now >= time + interval)
+    }
+}
@@ -0,0 +1,74 @@
+//! Peer exchange between the nodes, driven with `store.dispatch` over mocked services.
+
+use tezos_identity::Identity;
+
+use crate::config::{test_config, Config, TrustedPeer};
+use crate::peer::PeerStatus;
+use crate::peers::advertise::PeersAdvertiseBootstrapSendAction;
+use crate::service::mock::{address, connect, node, node_with_config, peer_status, pipe, pipe_all};
+use crate::service::{Service, TimeService};
+use crate::tick::TickAction;
+
+#[test]
+fn test_peers_advertise() {
+    let (a_address, b_address) = (address(1), address(2));
+    let (c_address, d_address, e_address) = (address(3), address(4), address(5));
+    let mut a = node(a_address);
+    let mut b = node_with_config(Config {
+        p2p_address: b_address,
+        identity: Identity::generate(0.0).unwrap(),
+        // don't ask for peers on tick.
+        peers_potential_min: 0,
+        ..test_config()
+    });
+    let mut c = node(c_address);
+    let mut d = node_with_config(Config {
+        p2p_address: d_address,
+        identity: Identity::generate(0.0).unwrap(),
+        private_node: true,
+        trusted_peers: vec![TrustedPeer {
+            address: b_address,
+            peer_id: None,
+        }],
+        ..test_config()
+    });
+    let mut e = node(e_address);
+
+    connect(&mut c, c_address, &mut b, b_address);
+    pipe_all(&mut c, c_address, &mut b, b_address);
+    connect(&mut d, d_address, &mut b, b_address);
+    pipe_all(&mut d, d_address, &mut b, b_address);
+    assert!(matches!(
+        peer_status(&b, d_address),
+        Some(PeerStatus::Handshaked(_))
+    ));
+
+    // `a` asks `b` for peers once handshaked, `b` advertises `c`, but
+    // not the private `d`.
+    connect(&mut a, a_address, &mut b, b_address);
+    pipe_all(&mut a, a_address, &mut b, b_address);
+    assert!(matches!(
+        peer_status(&a, c_address),
+        Some(PeerStatus::Potential)
+    ));
+    assert!(peer_status(&a, d_address).is_none());
+
+    // `b` doesn't answer again within `peers_advertise_interval`.
+    a.dispatch(PeersAdvertiseBootstrapSendAction { address: b_address }.into());
+    assert!(pipe(&mut a, a_address, &mut b, b_address));
+    assert!(!pipe(&mut b, b_address, &mut a, a_address));
+
+    // once it passes, `b` answers, but `a` ignores the advertised peers,
+    // since it accepted them from `b` too recently.
+    connect(&mut e, e_address, &mut b, b_address);
+    pipe_all(&mut e, e_address, &mut b, b_address);
+    let interval = b.state().config.peers_advertise_interval;
+    b.service.time.advance(interval);
+    let time = b.service().time().now();
+    b.dispatch(TickAction { time }.into());
+
+    a.dispatch(PeersAdvertiseBootstrapSendAction { address: b_address }.into());
+    assert!(pipe(&mut a, a_address, &mut b, b_address));
+    assert!(pipe(&mut b, b_address, &mut a, a_address));
+    assert!(peer_status(&a, e_address).is_none());
+}
@@ -9,3 +9,6 @@ pub use peers_dns_lookup_reducer::*;
 
 mod peers_dns_lookup_effects;
 pub use peers_dns_lookup_effects::*;
+
+#[cfg(test)]
+mod peers_dns_lookup_tests;
@@ -11,16 +11,22 @@ pub struct PeersDnsLookupInitAction {
     pub port: Port,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeersDnsLookupPendingAction {
+    pub address: String,
+    pub port: Port,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PeersDnsLookupErrorAction {
+    pub address: String,
+    pub port: Port,
     pub error: DnsLookupError,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PeersDnsLookupSuccessAction {
+    pub address: String,
+    pub port: Port,
     pub addresses: Vec<SocketAddr>,
 }
-
-/// Cleanup dns lookup state.
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct PeersDnsLookupCleanupAction;
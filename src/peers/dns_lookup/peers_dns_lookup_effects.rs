@@ -1,12 +1,13 @@
 use redux_rs::{ActionWithId, Store};
 
 use crate::peers::add::multi::PeersAddMultiAction;
+use crate::service::dns_service::DnsLookupRequest;
 use crate::service::{DnsService, Service};
 use crate::{action::Action, State};
 
 use super::{
-    PeersDnsLookupCleanupAction, PeersDnsLookupErrorAction, PeersDnsLookupStatus,
-    PeersDnsLookupSuccessAction,
+    peers_dns_lookup_key, DnsLookupError, PeersDnsLookupErrorAction, PeersDnsLookupInitAction,
+    PeersDnsLookupPendingAction, PeersDnsLookupStatus, PeersDnsLookupSuccessAction,
 };
 
 pub fn peers_dns_lookup_effects<S: Service>(
@@ -14,29 +15,157 @@ pub fn peers_dns_lookup_effects<S: Service>(
     action: &ActionWithId<Action>,
 ) {
     match &action.action {
+        Action::Tick(_) => {
+            // Lookups which never resolved, e.g. if the lookup thread
+            // is stuck, would otherwise stay pending forever.
+            let state = store.state.get();
+            let timeout = state.config.peers_dns_lookup_timeout;
+            let timed_out = state
+                .peers_dns_lookup
+                .values()
+                .filter(|dns_lookup_state| match dns_lookup_state.status {
+                    PeersDnsLookupStatus::Pending { time } => state.time >= time + timeout,
+                    _ => false,
+                })
+                .map(|dns_lookup_state| (dns_lookup_state.address.clone(), dns_lookup_state.port))
+                .collect::<Vec<_>>();
+            for (address, port) in timed_out {
+                store.dispatch(
+                    PeersDnsLookupErrorAction {
+                        address,
+                        port,
+                        error: DnsLookupError::Timeout,
+                    }
+                    .into(),
+                );
+            }
+
+            let state = store.state.get();
+            let config = &state.config;
+            let potential_low = state.peers_potential_len() < config.peers_potential_min;
+
+            if config.private_node {
+                // in private mode bootstrap peers aren't used, we only
+                // connect to the trusted peers.
+                return;
+            }
+
+            // Bootstrap dns names which haven't been looked up yet.
+            let not_started = config
+                .bootstrap_peers
+                .iter()
+                .filter(|(address, port)| {
+                    !state
+                        .peers_dns_lookup
+                        .contains_key(&peers_dns_lookup_key(address, *port))
+                })
+                .cloned();
+            // Finished lookups, which are due to be repeated.
+            let repeat = state
+                .peers_dns_lookup
+                .values()
+                .filter(|dns_lookup_state| {
+                    let backoff = dns_lookup_state.retry_backoff(
+                        config.peers_dns_lookup_retry_backoff,
+                        config.peers_dns_lookup_retry_backoff_max,
+                    );
+                    let (time, interval) = match &dns_lookup_state.status {
+                        PeersDnsLookupStatus::Success { time, .. } if potential_low => {
+                            (*time, config.peers_dns_lookup_retry_backoff)
+                        }
+                        PeersDnsLookupStatus::Success { time, .. } => {
+                            (*time, config.peers_dns_lookup_refresh_interval)
+                        }
+                        PeersDnsLookupStatus::Error { time, .. } => (*time, backoff),
+                        _ => return false,
+                    };
+                    state.time >= time + interval
+                })
+                .map(|dns_lookup_state| (dns_lookup_state.address.clone(), dns_lookup_state.port));
+            let lookups = not_started.chain(repeat).collect::<Vec<_>>();
+
+            let static_addresses = if potential_low {
+                config
+                    .bootstrap_addresses
+                    .iter()
+                    .filter(|address| !state.peers.contains_key(address))
+                    .filter(|address| !state.is_graylisted(address))
+                    .cloned()
+                    .collect()
+            } else {
+                vec![]
+            };
+
+            for (address, port) in lookups {
+                store.dispatch(PeersDnsLookupInitAction { address, port }.into());
+            }
+            if !static_addresses.is_empty() {
+                store.dispatch(
+                    PeersAddMultiAction {
+                        addresses: static_addresses,
+                    }
+                    .into(),
+                );
+            }
+        }
         Action::PeersDnsLookupInit(action) => {
-            let result = store
-                .service()
-                .dns()
-                .resolve_dns_name_to_peer_address(&action.address, action.port);
-            store.dispatch(match result {
-                Ok(addresses) => PeersDnsLookupSuccessAction { addresses }.into(),
-                Err(err) => PeersDnsLookupErrorAction { error: err.into() }.into(),
-            });
+            let key = peers_dns_lookup_key(&action.address, action.port);
+            match store.state.get().peers_dns_lookup.get(&key) {
+                Some(dns_lookup_state) => match dns_lookup_state.status {
+                    PeersDnsLookupStatus::Init => {}
+                    _ => return,
+                },
+                None => return,
+            }
+            let (address, port) = (action.address.clone(), action.port);
+            let request = DnsLookupRequest {
+                address: address.clone(),
+                port,
+            };
+            let next_action = match store.service.dns().request_send(request) {
+                Ok(()) => PeersDnsLookupPendingAction { address, port }.into(),
+                Err(_) => PeersDnsLookupErrorAction {
+                    address,
+                    port,
+                    error: DnsLookupError::ServiceUnavailable,
+                }
+                .into(),
+            };
+            store.dispatch(next_action);
+        }
+        Action::WakeupEvent(_) => {
+            // TODO: handle disconnected error.
+            while let Ok(resp) = store.service.dns().response_try_recv() {
+                let (address, port) = (resp.address, resp.port);
+                store.dispatch(match resp.result {
+                    Ok(addresses) => PeersDnsLookupSuccessAction {
+                        address,
+                        port,
+                        addresses,
+                    }
+                    .into(),
+                    Err(error) => PeersDnsLookupErrorAction {
+                        address,
+                        port,
+                        error,
+                    }
+                    .into(),
+                });
+            }
         }
-        Action::PeersDnsLookupSuccess(_) => {
-            let dns_lookup_state = match store.state.get().peers_dns_lookup.as_ref() {
+        Action::PeersDnsLookupSuccess(action) => {
+            let key = peers_dns_lookup_key(&action.address, action.port);
+            let dns_lookup_state = match store.state.get().peers_dns_lookup.get(&key) {
                 Some(v) => v,
                 None => return,
             };
             match &dns_lookup_state.status {
-                PeersDnsLookupStatus::Success { addresses } => {
+                PeersDnsLookupStatus::Success { addresses, .. } => {
                     let addresses = addresses.clone();
                     store.dispatch(PeersAddMultiAction { addresses }.into());
                 }
                 _ => {}
             }
-            store.dispatch(PeersDnsLookupCleanupAction {}.into());
         }
         _ => {}
     }
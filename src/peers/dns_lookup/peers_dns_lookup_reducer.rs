@@ -2,38 +2,71 @@ use redux_rs::ActionWithId;
 
 use crate::{action::Action, State};
 
-use super::{PeersDnsLookupState, PeersDnsLookupStatus};
+use super::{peers_dns_lookup_key, PeersDnsLookupState, PeersDnsLookupStatus};
 
 pub fn peers_dns_lookup_reducer(state: &mut State, action: &ActionWithId<Action>) {
     match &action.action {
         Action::PeersDnsLookupInit(action) => {
-            state.peers_dns_lookup = Some(PeersDnsLookupState {
-                address: action.address.clone(),
-                port: action.port,
-                status: PeersDnsLookupStatus::Init,
-            });
+            let dns_lookup_state = state
+                .peers_dns_lookup
+                .entry(peers_dns_lookup_key(&action.address, action.port))
+                .or_insert_with(|| PeersDnsLookupState {
+                    address: action.address.clone(),
+                    port: action.port,
+                    status: PeersDnsLookupStatus::Init,
+                    failures: 0,
+                });
+            match dns_lookup_state.status {
+                PeersDnsLookupStatus::Init | PeersDnsLookupStatus::Pending { .. } => {}
+                PeersDnsLookupStatus::Success { .. } | PeersDnsLookupStatus::Error { .. } => {
+                    dns_lookup_state.status = PeersDnsLookupStatus::Init;
+                }
+            }
         }
-        Action::PeersDnsLookupError(action) => {
-            if let Some(dns_lookup_state) = state.peers_dns_lookup.as_mut() {
+        Action::PeersDnsLookupPending(action) => {
+            let time = state.time;
+            if let Some(dns_lookup_state) = state
+                .peers_dns_lookup
+                .get_mut(&peers_dns_lookup_key(&action.address, action.port))
+            {
                 if let PeersDnsLookupStatus::Init = dns_lookup_state.status {
+                    dns_lookup_state.status = PeersDnsLookupStatus::Pending { time };
+                }
+            }
+        }
+        Action::PeersDnsLookupError(action) => {
+            let time = state.time;
+            if let Some(dns_lookup_state) = state
+                .peers_dns_lookup
+                .get_mut(&peers_dns_lookup_key(&action.address, action.port))
+            {
+                // lookup fails in `Init` if request couldn't be sent.
+                if let PeersDnsLookupStatus::Init | PeersDnsLookupStatus::Pending { .. } =
+                    dns_lookup_state.status
+                {
                     dns_lookup_state.status = PeersDnsLookupStatus::Error {
                         error: action.error,
+                        time,
                     };
+                    dns_lookup_state.failures += 1;
                 }
             }
         }
         Action::PeersDnsLookupSuccess(action) => {
-            if let Some(dns_lookup_state) = state.peers_dns_lookup.as_mut() {
-                if let PeersDnsLookupStatus::Init = dns_lookup_state.status {
+            let time = state.time;
+            if let Some(dns_lookup_state) = state
+                .peers_dns_lookup
+                .get_mut(&peers_dns_lookup_key(&action.address, action.port))
+            {
+                if let PeersDnsLookupStatus::Pending { .. } = dns_lookup_state.status {
                     dns_lookup_state.status = PeersDnsLookupStatus::Success {
                         addresses: action.addresses.clone(),
+                        time,
                     };
+                    dns_lookup_state.failures = 0;
                 }
             }
         }
-        Action::PeersDnsLookupCleanup(_) => {
-            state.peers_dns_lookup.take();
-        }
         _ => {}
     }
 }
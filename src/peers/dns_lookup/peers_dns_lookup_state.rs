@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
+use std::time::{Duration, SystemTime};
 
 use crate::Port;
 
@@ -48,6 +49,10 @@ pub enum DnsLookupError {
     /// You should convert this `LookupError` into an IO error directly. Note
     /// that the error code is set to 0 in the case this is returned.
     IO,
+    /// Lookup request couldn't be sent to the dns service.
+    ServiceUnavailable,
+    /// Lookup didn't resolve within `config.peers_dns_lookup_timeout`.
+    Timeout,
 }
 
 impl From<dns_lookup::LookupError> for DnsLookupError {
@@ -74,8 +79,18 @@ impl From<dns_lookup::LookupError> for DnsLookupError {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum PeersDnsLookupStatus {
     Init,
-    Success { addresses: Vec<SocketAddr> },
-    Error { error: DnsLookupError },
+    /// Lookup request was sent to the dns service.
+    Pending {
+        time: SystemTime,
+    },
+    Success {
+        addresses: Vec<SocketAddr>,
+        time: SystemTime,
+    },
+    Error {
+        error: DnsLookupError,
+        time: SystemTime,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -83,4 +98,20 @@ pub struct PeersDnsLookupState {
     pub address: String,
     pub port: Port,
     pub status: PeersDnsLookupStatus,
+    /// Number of consecutive failed lookups.
+    pub failures: u32,
+}
+
+/// Key of the lookup in `state.peers_dns_lookup`. The same name with
+/// different ports are separate lookups, as they yield different addresses.
+pub fn peers_dns_lookup_key(address: &str, port: Port) -> String {
+    format!("{}:{}", address, port)
+}
+
+impl PeersDnsLookupState {
+    /// Delay before retrying the failed lookup (exponential backoff).
+    pub fn retry_backoff(&self, base: Duration, max: Duration) -> Duration {
+        let exp = self.failures.saturating_sub(1).min(16);
+        base.checked_mul(1 << exp).unwrap_or(max).min(max)
+    }
 }
@@ -0,0 +1,69 @@
+//! Bootstrap peers' dns lookups, driven with `store.dispatch` over mocked services.
+
+use std::time::Duration;
+
+use tezos_identity::Identity;
+
+use crate::config::{test_config, Config};
+use crate::service::mock::{address, node_with_config, MockStore};
+use crate::service::{Service, TimeService};
+use crate::tick::TickAction;
+
+use super::{DnsLookupError, PeersDnsLookupStatus};
+
+#[test]
+fn test_dns_lookup_same_name_different_ports() {
+    let store = node_with_config(Config {
+        p2p_address: address(1),
+        identity: Identity::generate(0.0).unwrap(),
+        bootstrap_peers: vec![
+            ("boot.test".to_owned(), 9732),
+            ("boot.test".to_owned(), 9733),
+        ],
+        ..test_config()
+    });
+
+    let dns_lookup = &store.state().peers_dns_lookup;
+    assert_eq!(dns_lookup.len(), 2);
+    assert_eq!(dns_lookup["boot.test:9732"].port, 9732);
+    assert_eq!(dns_lookup["boot.test:9733"].port, 9733);
+}
+
+#[test]
+fn test_dns_lookup_pending_timeout() {
+    let mut store = node_with_config(Config {
+        p2p_address: address(1),
+        identity: Identity::generate(0.0).unwrap(),
+        bootstrap_peers: vec![("boot.test".to_owned(), 9732)],
+        ..test_config()
+    });
+    // lookup never resolves.
+    store.service.dns.responses.clear();
+    let is_pending = |store: &MockStore| {
+        matches!(
+            store.state().peers_dns_lookup["boot.test:9732"].status,
+            PeersDnsLookupStatus::Pending { .. }
+        )
+    };
+    assert!(is_pending(&store));
+
+    let timeout = store.state().config.peers_dns_lookup_timeout;
+    store.service.time.advance(timeout - Duration::from_secs(1));
+    let time = store.service().time().now();
+    store.dispatch(TickAction { time }.into());
+    assert!(is_pending(&store));
+
+    store.service.time.advance(Duration::from_secs(1));
+    let time = store.service().time().now();
+    store.dispatch(TickAction { time }.into());
+    let dns_lookup_state = &store.state().peers_dns_lookup["boot.test:9732"];
+    assert!(matches!(
+        dns_lookup_state.status,
+        PeersDnsLookupStatus::Error {
+            error: DnsLookupError::Timeout,
+            ..
+        }
+    ));
+    assert_eq!(dns_lookup_state.failures, 1);
+    assert_eq!(store.service.dns.requests.len(), 1);
+}
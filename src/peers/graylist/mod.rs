@@ -0,0 +1,14 @@
+mod peers_graylist_state;
+pub use peers_graylist_state::*;
+
+mod peers_graylist_actions;
+pub use peers_graylist_actions::*;
+
+mod peers_graylist_reducer;
+pub use peers_graylist_reducer::*;
+
+mod peers_graylist_effects;
+pub use peers_graylist_effects::*;
+
+#[cfg(test)]
+mod peers_graylist_tests;
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
+
+use super::PeerGraylistReason;
+
+/// Graylist peer's ip and disconnect the peer.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeersGraylistAddressAction {
+    pub address: SocketAddr,
+    pub reason: PeerGraylistReason,
+}
+
+/// Lift graylist from the ip.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeersGraylistIpRemoveAction {
+    pub ip: IpAddr,
+}
@@ -0,0 +1,37 @@
+use redux_rs::{ActionWithId, Store};
+
+use crate::peer::disconnection::PeerDisconnectAction;
+use crate::service::Service;
+use crate::{action::Action, State};
+
+use super::PeersGraylistIpRemoveAction;
+
+pub fn peers_graylist_effects<S>(store: &mut Store<State, S, Action>, action: &ActionWithId<Action>)
+where
+    S: Service,
+{
+    match &action.action {
+        Action::PeersGraylistAddress(action) => {
+            store.dispatch(
+                PeerDisconnectAction {
+                    address: action.address,
+                }
+                .into(),
+            );
+        }
+        Action::Tick(_) => {
+            let state = store.state.get();
+            let expired = state
+                .peers_graylist
+                .iter()
+                .filter(|(_, graylist)| graylist.until <= state.time)
+                .map(|(ip, _)| *ip)
+                .collect::<Vec<_>>();
+
+            for ip in expired {
+                store.dispatch(PeersGraylistIpRemoveAction { ip }.into());
+            }
+        }
+        _ => {}
+    }
+}
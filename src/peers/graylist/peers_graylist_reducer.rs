@@ -0,0 +1,30 @@
+use redux_rs::ActionWithId;
+
+use crate::{action::Action, State};
+
+use super::{graylist_ip_canonical, PeerGraylistState};
+
+pub fn peers_graylist_reducer(state: &mut State, action: &ActionWithId<Action>) {
+    match &action.action {
+        Action::PeersGraylistAddress(action) => {
+            let ip = graylist_ip_canonical(action.address.ip());
+            // trusted peers are never graylisted, so neither is their ip.
+            if state.is_trusted_ip(ip) {
+                return;
+            }
+            let since = state.time;
+            state.peers_graylist.insert(
+                ip,
+                PeerGraylistState {
+                    reason: action.reason.clone(),
+                    since,
+                    until: since + state.config.peers_graylist_timeout,
+                },
+            );
+        }
+        Action::PeersGraylistIpRemove(action) => {
+            state.peers_graylist.remove(&action.ip);
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::time::SystemTime;
+
+use crate::peer::handshaking::PeerHandshakingError;
+use crate::peer::message::read::PeerMessageReadError;
+use crate::peer::score::PeerScore;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum PeerGraylistReason {
+    HandshakingError(PeerHandshakingError),
+    MessageReadError(PeerMessageReadError),
+    /// Peer's score fell below `config.peer_score_graylist_threshold`.
+    LowScore(PeerScore),
+}
+
+/// Graylisted (temporarily blacklisted) peer's ip.
+///
+/// While ip is graylisted, we won't connect to it and will reject
+/// incoming connections from it right when we accept them. Peer can
+/// freely change its port, so whole ip is graylisted.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerGraylistState {
+    pub reason: PeerGraylistReason,
+    pub since: SystemTime,
+    /// Graylist entry is lifted once this time passes.
+    pub until: SystemTime,
+}
+
+/// Ipv4-mapped ipv6 ip converted to ipv4, so that the peer maps to the
+/// same graylist entry, regardless of how it connected.
+pub fn graylist_ip_canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => match v6.segments() {
+            [0, 0, 0, 0, 0, 0xffff, hi, lo] => {
+                IpAddr::from([(hi >> 8) as u8, hi as u8, (lo >> 8) as u8, lo as u8])
+            }
+            _ => ip,
+        },
+        IpAddr::V4(_) => ip,
+    }
+}
@@ -0,0 +1,62 @@
+//! Graylisting peers' ips, driven with `store.dispatch` over mocked services.
+
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use crate::event::P2pServerEvent;
+use crate::peers::add::multi::PeersAddMultiAction;
+use crate::peers::graylist::{PeerGraylistReason, PeersGraylistAddressAction};
+use crate::service::mock::{address, node, peer_status};
+use crate::service::{Service, TimeService};
+use crate::tick::TickAction;
+
+#[test]
+fn test_graylist() {
+    let mut store = node(address(1));
+    let graylisted = address(2);
+    // another node on the same host.
+    let same_host = SocketAddr::new(graylisted.ip(), graylisted.port() + 1);
+
+    store.dispatch(
+        PeersGraylistAddressAction {
+            address: graylisted,
+            reason: PeerGraylistReason::LowScore(-100),
+        }
+        .into(),
+    );
+    store.dispatch(
+        PeersAddMultiAction {
+            addresses: vec![graylisted, same_host],
+        }
+        .into(),
+    );
+    // peer can't get around graylist by changing its port.
+    assert!(peer_status(&store, graylisted).is_none());
+    assert!(peer_status(&store, same_host).is_none());
+
+    // incoming connection is rejected right when it's accepted.
+    let incoming = SocketAddr::new(graylisted.ip(), 43210);
+    store.service.mio.incoming_connection_add(incoming);
+    store.dispatch(P2pServerEvent {}.into());
+    assert!(peer_status(&store, incoming).is_none());
+    assert_eq!(store.service.mio.peers_len(), 0);
+
+    // ipv4-mapped ipv6 address is the same peer.
+    let mapped = match graylisted.ip() {
+        IpAddr::V4(ip) => SocketAddr::new(ip.to_ipv6_mapped().into(), graylisted.port()),
+        IpAddr::V6(_) => unreachable!(),
+    };
+    assert!(store.state().is_graylisted(&mapped));
+
+    // graylist is lifted on tick, once timeout passes.
+    let timeout = store.state().config.peers_graylist_timeout;
+    store.service.time.advance(timeout - Duration::from_secs(1));
+    let time = store.service().time().now();
+    store.dispatch(TickAction { time }.into());
+    assert!(store.state().is_graylisted(&graylisted));
+
+    store.service.time.advance(Duration::from_secs(1));
+    let time = store.service().time().now();
+    store.dispatch(TickAction { time }.into());
+    assert!(!store.state().is_graylisted(&graylisted));
+}
@@ -1,4 +1,7 @@
 pub mod dns_lookup;
 
 pub mod add;
+pub mod advertise;
+pub mod graylist;
 pub mod remove;
+pub mod timeouts;
@@ -7,7 +7,20 @@ pub fn peers_remove_reducer(state: &mut State, action: &ActionWithId<Action>) {
         Action::PeersRemove(action) => {
             if let Some(peer) = state.peers.get(&action.address) {
                 // we aren't allowed to remove peer until peer is disconnected.
-                if matches!(&peer.status, PeerStatus::Disconnected) {
+                if !matches!(&peer.status, PeerStatus::Disconnected) {
+                    return;
+                }
+                // keep the peer, whose outgoing connection failed, as potential
+                // one, so that its connection attempts history isn't lost.
+                // Trusted peers are always kept, so that we reconnect to them.
+                let attempts = peer.connection_attempts.count;
+                if state.is_trusted_address(&action.address)
+                    || (attempts > 0 && attempts < state.config.peer_connection_attempts_max)
+                {
+                    if let Some(peer) = state.peers.get_mut(&action.address) {
+                        peer.status = PeerStatus::Potential;
+                    }
+                } else {
                     state.peers.remove(&action.address);
                 }
             }
@@ -0,0 +1,5 @@
+mod peers_timeouts_actions;
+pub use peers_timeouts_actions::*;
+
+mod peers_timeouts_effects;
+pub use peers_timeouts_effects::*;
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+use crate::peer::handshaking::PeerHandshakingStage;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum PeerTimeout {
+    Connecting,
+    Handshaking(PeerHandshakingStage),
+}
+
+/// Check if any of the peers have been stuck in some stage for too long.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeersTimeoutsCheckAction {}
+
+/// Peer exceeded timeout for the stage it's in.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerTimeoutAction {
+    pub address: SocketAddr,
+    pub timeout: PeerTimeout,
+}
@@ -0,0 +1,71 @@
+use redux_rs::{ActionWithId, Store};
+
+use crate::peer::connection::incoming::PeerConnectionIncomingState;
+use crate::peer::connection::outgoing::PeerConnectionOutgoingState;
+use crate::peer::connection::PeerConnectionState;
+use crate::peer::disconnection::PeerDisconnectAction;
+use crate::peer::PeerStatus;
+use crate::service::Service;
+use crate::{action::Action, State};
+
+use super::{PeerTimeout, PeerTimeoutAction};
+
+pub fn peers_timeouts_effects<S: Service>(
+    store: &mut Store<State, S, Action>,
+    action: &ActionWithId<Action>,
+) {
+    match &action.action {
+        Action::PeersTimeoutsCheck(_) => {
+            let state = store.state.get();
+            let config = &state.config;
+            let now = state.time;
+
+            let timeouts = state
+                .peers
+                .iter()
+                .filter_map(|(address, peer)| {
+                    let (started_at, timeout, limit) = match &peer.status {
+                        PeerStatus::Connecting(PeerConnectionState::Outgoing(
+                            PeerConnectionOutgoingState::Pending { started_at, .. },
+                        ))
+                        | PeerStatus::Connecting(PeerConnectionState::Incoming(
+                            PeerConnectionIncomingState::Pending { started_at, .. },
+                        )) => (
+                            started_at,
+                            PeerTimeout::Connecting,
+                            config.peer_connecting_timeout,
+                        ),
+                        PeerStatus::Handshaking(handshaking) => {
+                            let stage = handshaking.status.stage();
+                            (
+                                &handshaking.stage_started_at,
+                                PeerTimeout::Handshaking(stage),
+                                config.peer_handshaking_timeout(stage),
+                            )
+                        }
+                        _ => return None,
+                    };
+                    let elapsed = now.duration_since(*started_at).unwrap_or_default();
+                    if elapsed >= limit {
+                        Some((*address, timeout))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            for (address, timeout) in timeouts {
+                store.dispatch(PeerTimeoutAction { address, timeout }.into());
+            }
+        }
+        Action::PeerTimeout(action) => {
+            store.dispatch(
+                PeerDisconnectAction {
+                    address: action.address,
+                }
+                .into(),
+            );
+        }
+        _ => {}
+    }
+}
@@ -12,16 +12,27 @@ use crate::peer::connection::incoming::peer_connection_incoming_reducer;
 use crate::peer::connection::outgoing::peer_connection_outgoing_reducer;
 use crate::peer::disconnection::peer_disconnection_reducer;
 use crate::peer::handshaking::peer_handshaking_reducer;
+use crate::peer::message::read::peer_message_read_reducer;
+use crate::peer::message::write::peer_message_write_reducer;
+use crate::peer::score::peer_score_reducer;
 
 use crate::peers::add::multi::peers_add_multi_reducer;
 use crate::peers::add::peers_add_reducer;
+use crate::peers::advertise::peers_advertise_reducer;
 use crate::peers::dns_lookup::peers_dns_lookup_reducer;
+use crate::peers::graylist::peers_graylist_reducer;
 use crate::peers::remove::peers_remove_reducer;
 
+use crate::chain::block_headers::chain_block_headers_reducer;
+use crate::chain::current_head::chain_current_head_reducer;
+
 use crate::storage::block_header::put::storage_block_header_put_reducer;
+use crate::storage::peers::storage_peers_reducer;
 use crate::storage::request::storage_request_reducer;
 use crate::storage::state_snapshot::create::storage_state_snapshot_create_reducer;
 
+use crate::tick::tick_reducer;
+
 pub fn last_action_id_reducer(state: &mut State, action: &ActionWithId<Action>) {
     state.last_action_id = action.id;
 }
@@ -32,20 +43,29 @@ pub fn reducer(state: &mut State, action: &ActionWithId<Action>) {
         action,
         // needs to be first!
         storage_state_snapshot_create_reducer,
+        tick_reducer,
         peers_dns_lookup_reducer,
         peers_add_multi_reducer,
         peers_add_reducer,
         peers_remove_reducer,
+        peers_graylist_reducer,
+        peers_advertise_reducer,
         peer_connection_outgoing_reducer,
         peer_connection_incoming_accept_reducer,
         peer_connection_incoming_reducer,
         peer_handshaking_reducer,
+        peer_message_write_reducer,
+        peer_message_read_reducer,
         peer_binary_message_write_reducer,
         peer_binary_message_read_reducer,
         peer_chunk_write_reducer,
         peer_chunk_read_reducer,
+        peer_score_reducer,
         peer_disconnection_reducer,
+        chain_current_head_reducer,
+        chain_block_headers_reducer,
         storage_block_header_put_reducer,
+        storage_peers_reducer,
         storage_request_reducer,
         // needs to be last!
         last_action_id_reducer
@@ -0,0 +1,304 @@
+//! Replay of the persisted action log.
+//!
+//! Loads state snapshot, re-runs [crate::reducer] over the stored
+//! actions and checks that resulting state matches every state snapshot
+//! encountered along the way. Snapshot with key `N` is the state after
+//! the action with id `N` was reduced.
+//!
+//! On mismatch, actions since the last matched snapshot are replayed
+//! again one by one to find the action which introduced the divergence.
+
+use redux_rs::{ActionId, ActionWithId};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use storage::{PersistentStorage, ReduxActionStorage, ReduxStateStorage, StorageError};
+
+use crate::action::Action;
+use crate::{reducer, State};
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Storage(StorageError),
+    Serialize(serde_json::Error),
+    SnapshotNotFound { action_id: u64 },
+}
+
+impl From<StorageError> for ReplayError {
+    fn from(error: StorageError) -> Self {
+        Self::Storage(error)
+    }
+}
+
+impl From<serde_json::Error> for ReplayError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Serialize(error)
+    }
+}
+
+/// Difference between expected and actual value at the given path.
+#[derive(Serialize, Debug, Clone)]
+pub struct StateDiff {
+    pub path: String,
+    pub expected: Option<Value>,
+    pub actual: Option<Value>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub enum ReplayResult {
+    /// Every snapshot in the range matched the replayed state.
+    Matched {
+        last_action_id: u64,
+        snapshots_checked: usize,
+    },
+    /// Replayed state didn't match the snapshot `snapshot_action_id`.
+    ///
+    /// `action_id` is the first action in range
+    /// `(last_matched_action_id, snapshot_action_id]` after which replayed
+    /// state at the diverged paths stays as it's in the mismatched state.
+    Diverged {
+        last_matched_action_id: u64,
+        snapshot_action_id: u64,
+        action_id: u64,
+        diff: Vec<StateDiff>,
+    },
+}
+
+fn value_diff(path: String, expected: &Value, actual: &Value, diff: &mut Vec<StateDiff>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let keys = expected
+                .keys()
+                .chain(actual.keys())
+                .collect::<BTreeSet<_>>();
+            for key in keys {
+                let path = format!("{}.{}", path, key);
+                match (expected.get(key), actual.get(key)) {
+                    (Some(expected), Some(actual)) => value_diff(path, expected, actual, diff),
+                    (expected, actual) => diff.push(StateDiff {
+                        path,
+                        expected: expected.cloned(),
+                        actual: actual.cloned(),
+                    }),
+                }
+            }
+        }
+        (Value::Array(expected_arr), Value::Array(actual_arr))
+            if expected_arr.len() == actual_arr.len() =>
+        {
+            for (i, (expected, actual)) in expected_arr.iter().zip(actual_arr).enumerate() {
+                value_diff(format!("{}[{}]", path, i), expected, actual, diff);
+            }
+        }
+        (expected, actual) => {
+            if expected != actual {
+                diff.push(StateDiff {
+                    path,
+                    expected: Some(expected.clone()),
+                    actual: Some(actual.clone()),
+                });
+            }
+        }
+    }
+}
+
+/// Paths at which `actual` state differs from the `expected` one.
+pub fn state_diff(expected: &State, actual: &State) -> Result<Vec<StateDiff>, ReplayError> {
+    let mut diff = vec![];
+    value_diff(
+        "state".to_owned(),
+        &serde_json::to_value(expected)?,
+        &serde_json::to_value(actual)?,
+        &mut diff,
+    );
+    Ok(diff)
+}
+
+/// Whether one path is the same as, or nested in, the other one.
+fn paths_overlap(a: &str, b: &str) -> bool {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    match long.strip_prefix(short) {
+        Some(rest) => rest.is_empty() || rest.starts_with('.') || rest.starts_with('['),
+        None => false,
+    }
+}
+
+/// Replay actions `(from, to]` one by one and find the first one after
+/// which replayed state at the `diff` paths stays equal to the
+/// `diverged` state (state after action `to`).
+///
+/// Snapshots in between don't exist, so if replay kept the value from
+/// the snapshot `from` and only the recorded run changed it, the first
+/// action after `from` is reported.
+fn diverged_action_id(
+    snapshot_storage: &ReduxStateStorage,
+    action_storage: &ReduxActionStorage,
+    from: u64,
+    to: u64,
+    diverged: &State,
+    diff: &[StateDiff],
+) -> Result<u64, ReplayError> {
+    let mut state: State = snapshot_storage
+        .get(&from)?
+        .ok_or(ReplayError::SnapshotNotFound { action_id: from })?;
+    let diverged = serde_json::to_value(diverged)?;
+    // Last action after which the path still differed from `diverged`.
+    let mut last_differed = vec![from; diff.len()];
+
+    for action_id in from..to {
+        if action_id > from {
+            let action = match action_storage.get::<Action>(&action_id)? {
+                Some(v) => v,
+                None => break,
+            };
+            reducer(
+                &mut state,
+                &ActionWithId {
+                    id: ActionId::new_unchecked(action_id),
+                    action,
+                },
+            );
+        }
+        let mut step_diff = vec![];
+        value_diff(
+            "state".to_owned(),
+            &serde_json::to_value(&state)?,
+            &diverged,
+            &mut step_diff,
+        );
+        for (i, path_diff) in diff.iter().enumerate() {
+            let differs = step_diff
+                .iter()
+                .any(|d| paths_overlap(&d.path, &path_diff.path));
+            if differs {
+                last_differed[i] = action_id;
+            }
+        }
+    }
+
+    Ok(last_differed.into_iter().min().unwrap_or(from) + 1)
+}
+
+/// Replay actions starting from the snapshot `from` until action `to`
+/// (inclusive), or until the end of the action log.
+pub fn replay(
+    storage: &PersistentStorage,
+    from: u64,
+    to: Option<u64>,
+) -> Result<ReplayResult, ReplayError> {
+    let snapshot_storage = ReduxStateStorage::new(storage);
+    let action_storage = ReduxActionStorage::new(storage);
+
+    let mut state: State = snapshot_storage
+        .get(&from)?
+        .ok_or(ReplayError::SnapshotNotFound { action_id: from })?;
+    let mut last_action_id = from;
+    let mut last_matched_action_id = from;
+    let mut snapshots_checked = 0;
+
+    for action_id in (from + 1)..=to.unwrap_or(u64::MAX) {
+        let action = match action_storage.get::<Action>(&action_id)? {
+            Some(v) => v,
+            None => break,
+        };
+        reducer(
+            &mut state,
+            &ActionWithId {
+                id: ActionId::new_unchecked(action_id),
+                action,
+            },
+        );
+        last_action_id = action_id;
+
+        if let Some(expected) = snapshot_storage.get::<State>(&action_id)? {
+            let diff = state_diff(&expected, &state)?;
+            if !diff.is_empty() {
+                return Ok(ReplayResult::Diverged {
+                    last_matched_action_id,
+                    snapshot_action_id: action_id,
+                    action_id: diverged_action_id(
+                        &snapshot_storage,
+                        &action_storage,
+                        last_matched_action_id,
+                        action_id,
+                        &state,
+                        &diff,
+                    )?,
+                    diff,
+                });
+            }
+            last_matched_action_id = action_id;
+            snapshots_checked += 1;
+        }
+    }
+
+    Ok(ReplayResult::Matched {
+        last_action_id,
+        snapshots_checked,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use super::*;
+    use crate::config::test_config;
+    use crate::peers::add::multi::PeersAddMultiAction;
+    use crate::tmp::persistent_storage::init_storage;
+
+    fn address(n: u8) -> SocketAddr {
+        ([10, 0, 0, n], 9732).into()
+    }
+
+    #[test]
+    fn test_replay_tampered_snapshot() {
+        let data_dir =
+            std::env::temp_dir().join(format!("tezedge_redux_replay_{}", std::process::id()));
+        let (storage, _) = init_storage(&data_dir);
+        let snapshot_storage = ReduxStateStorage::new(&storage);
+        let action_storage = ReduxActionStorage::new(&storage);
+
+        let mut state = State::new(test_config());
+        snapshot_storage.put(&0, &state).unwrap();
+        for id in 1..=4 {
+            let action: Action = PeersAddMultiAction {
+                addresses: vec![address(id as u8)],
+            }
+            .into();
+            action_storage.put(&id, &action).unwrap();
+            reducer(
+                &mut state,
+                &ActionWithId {
+                    id: ActionId::new_unchecked(id),
+                    action,
+                },
+            );
+        }
+
+        // as if action `3` didn't add the peer when recorded.
+        let mut tampered = state.clone();
+        tampered.peers.remove(&address(3));
+        snapshot_storage.put(&4, &tampered).unwrap();
+
+        let result = replay(&storage, 0, None).unwrap();
+        std::fs::remove_dir_all(&data_dir).unwrap();
+
+        match result {
+            ReplayResult::Diverged {
+                last_matched_action_id,
+                snapshot_action_id,
+                action_id,
+                diff,
+            } => {
+                assert_eq!(last_matched_action_id, 0);
+                assert_eq!(snapshot_action_id, 4);
+                assert_eq!(action_id, 3);
+                assert_eq!(diff.len(), 1);
+                assert_eq!(diff[0].path, format!("state.peers.{}", address(3)));
+            }
+            result => panic!("expected divergence, got: {:?}", result),
+        }
+    }
+}
@@ -1,55 +1,139 @@
 use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::thread;
+
+use crate::peers::dns_lookup::DnsLookupError;
+use crate::Port;
+
+use super::service_channel::{
+    worker_channel, RequestSendError, ResponseTryRecvError, ServiceWorkerRequester,
+    ServiceWorkerResponder, ServiceWorkerResponderSender,
+};
 
 pub trait DnsService {
-    /// Try to resolve common peer name into Socket Address representation.
-    fn resolve_dns_name_to_peer_address(
+    /// Send the dns lookup request to the worker.
+    fn request_send(
         &mut self,
-        address: &str,
-        port: u16,
-    ) -> Result<Vec<SocketAddr>, dns_lookup::LookupError>;
+        req: DnsLookupRequest,
+    ) -> Result<(), RequestSendError<DnsLookupRequest>>;
+
+    /// Try to receive/read queued response, if there is any.
+    fn response_try_recv(&mut self) -> Result<DnsLookupResponse, ResponseTryRecvError>;
+}
+
+type DnsWorkerRequester = ServiceWorkerRequester<DnsLookupRequest, DnsLookupResponse>;
+type DnsWorkerResponder = ServiceWorkerResponder<DnsLookupRequest, DnsLookupResponse>;
+
+#[derive(Debug, Clone)]
+pub struct DnsLookupRequest {
+    pub address: String,
+    pub port: Port,
 }
 
-#[derive(Debug, Default, Clone)]
-pub struct DnsServiceDefault;
+#[derive(Debug, Clone)]
+pub struct DnsLookupResponse {
+    pub address: String,
+    pub port: Port,
+    pub result: Result<Vec<SocketAddr>, DnsLookupError>,
+}
+
+/// Try to resolve common peer name into Socket Address representation.
+pub fn resolve_dns_name_to_peer_address(
+    address: &str,
+    port: u16,
+) -> Result<Vec<SocketAddr>, dns_lookup::LookupError> {
+    // filter just for [`AI_SOCKTYPE SOCK_STREAM`]
+    let hints = dns_lookup::AddrInfoHints {
+        socktype: i32::from(dns_lookup::SockType::Stream),
+        ..dns_lookup::AddrInfoHints::default()
+    };
+
+    let addrs =
+        dns_lookup::getaddrinfo(Some(address), Some(port.to_string().as_str()), Some(hints))?
+            .filter(Result::is_ok)
+            .map(Result::unwrap)
+            .filter(|info: &dns_lookup::AddrInfo| {
+                // filter just IP_NET and IP_NET6 addresses
+                dns_lookup::AddrFamily::Inet.eq(&info.address)
+                    || dns_lookup::AddrFamily::Inet6.eq(&info.address)
+            })
+            .map(|info: dns_lookup::AddrInfo| {
+                // convert to uniform IPv6 format
+                match &info.sockaddr {
+                    SocketAddr::V4(ipv4) => {
+                        // convert ipv4 to ipv6
+                        SocketAddr::new(IpAddr::V6(ipv4.ip().to_ipv6_mapped()), ipv4.port())
+                    }
+                    SocketAddr::V6(_) => info.sockaddr,
+                }
+            })
+            .collect();
+    Ok(addrs)
+}
+
+#[derive(Debug)]
+pub struct DnsServiceDefault {
+    worker_channel: DnsWorkerRequester,
+}
 
 impl DnsServiceDefault {
-    pub fn new() -> Self {
-        Self {}
+    fn lookup(req: DnsLookupRequest, mut sender: ServiceWorkerResponderSender<DnsLookupResponse>) {
+        let result =
+            resolve_dns_name_to_peer_address(&req.address, req.port).map_err(|err| err.into());
+        let _ = sender.send(DnsLookupResponse {
+            address: req.address,
+            port: req.port,
+            result,
+        });
+    }
+
+    fn run_worker(mut channel: DnsWorkerResponder) {
+        // `getaddrinfo` is blocking, so each lookup gets its own thread
+        // in order for the slow lookup not to hold back the others.
+        while let Ok(req) = channel.recv() {
+            let (address, port) = (req.address.clone(), req.port);
+            let sender = channel.sender();
+            let spawned = thread::Builder::new()
+                .name("dns-lookup-thread".to_owned())
+                .spawn(move || Self::lookup(req, sender));
+
+            if let Err(err) = spawned {
+                eprintln!("failed to spawn dns lookup thread! Error: {:?}", err);
+                let _ = channel.send(DnsLookupResponse {
+                    address,
+                    port,
+                    result: Err(DnsLookupError::ServiceUnavailable),
+                });
+            }
+        }
+    }
+
+    // TODO: remove unwraps
+    pub fn init(waker: Arc<mio::Waker>) -> Self {
+        let (requester, responder) = worker_channel(waker);
+
+        thread::Builder::new()
+            .name("dns-thread".to_owned())
+            .spawn(move || Self::run_worker(responder))
+            .unwrap();
+
+        Self {
+            worker_channel: requester,
+        }
     }
 }
 
 impl DnsService for DnsServiceDefault {
-    fn resolve_dns_name_to_peer_address(
+    #[inline(always)]
+    fn request_send(
         &mut self,
-        address: &str,
-        port: u16,
-    ) -> Result<Vec<SocketAddr>, dns_lookup::LookupError> {
-        // filter just for [`AI_SOCKTYPE SOCK_STREAM`]
-        let hints = dns_lookup::AddrInfoHints {
-            socktype: i32::from(dns_lookup::SockType::Stream),
-            ..dns_lookup::AddrInfoHints::default()
-        };
-
-        let addrs =
-            dns_lookup::getaddrinfo(Some(address), Some(port.to_string().as_str()), Some(hints))?
-                .filter(Result::is_ok)
-                .map(Result::unwrap)
-                .filter(|info: &dns_lookup::AddrInfo| {
-                    // filter just IP_NET and IP_NET6 addresses
-                    dns_lookup::AddrFamily::Inet.eq(&info.address)
-                        || dns_lookup::AddrFamily::Inet6.eq(&info.address)
-                })
-                .map(|info: dns_lookup::AddrInfo| {
-                    // convert to uniform IPv6 format
-                    match &info.sockaddr {
-                        SocketAddr::V4(ipv4) => {
-                            // convert ipv4 to ipv6
-                            SocketAddr::new(IpAddr::V6(ipv4.ip().to_ipv6_mapped()), ipv4.port())
-                        }
-                        SocketAddr::V6(_) => info.sockaddr,
-                    }
-                })
-                .collect();
-        Ok(addrs)
+        req: DnsLookupRequest,
+    ) -> Result<(), RequestSendError<DnsLookupRequest>> {
+        self.worker_channel.send(req)
+    }
+
+    #[inline(always)]
+    fn response_try_recv(&mut self) -> Result<DnsLookupResponse, ResponseTryRecvError> {
+        self.worker_channel.try_recv()
     }
 }
@@ -120,6 +120,12 @@ impl MioServiceDefault {
         }
     }
 
+    /// Address we listen on for incoming connections. If it was passed
+    /// with port `0`, it's resolved to the actual port once listening.
+    pub fn listen_addr(&self) -> SocketAddr {
+        self.listen_addr
+    }
+
     /// Waker can be used to wake up mio from another thread.
     pub fn waker(&self) -> Arc<mio::Waker> {
         self.waker.clone()
@@ -185,6 +191,7 @@ impl MioService for MioServiceDefault {
             socket.bind(self.listen_addr)?;
 
             let mut server = socket.listen(self.backlog_size)?;
+            self.listen_addr = server.local_addr()?;
 
             self.poll.registry().register(
                 &mut server,
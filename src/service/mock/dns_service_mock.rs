@@ -0,0 +1,54 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+
+use crate::peers::dns_lookup::DnsLookupError;
+use crate::service::dns_service::{DnsLookupRequest, DnsLookupResponse};
+use crate::service::service_channel::{RequestSendError, ResponseTryRecvError};
+use crate::service::DnsService;
+
+#[derive(Debug, Default, Clone)]
+pub struct DnsServiceMock {
+    /// Ips that dns name resolves to. Lookup of the missing name fails.
+    pub records: HashMap<String, Vec<IpAddr>>,
+    /// Lookup requests sent to the service.
+    pub requests: Vec<DnsLookupRequest>,
+    /// Responses which node will receive.
+    ///
+    /// Lookups are resolved right away, but the node only receives
+    /// them after the `WakeupEvent`.
+    pub responses: VecDeque<DnsLookupResponse>,
+}
+
+impl DnsServiceMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DnsService for DnsServiceMock {
+    fn request_send(
+        &mut self,
+        req: DnsLookupRequest,
+    ) -> Result<(), RequestSendError<DnsLookupRequest>> {
+        let result = match self.records.get(&req.address) {
+            Some(ips) => Ok(ips
+                .iter()
+                .map(|ip| SocketAddr::new(*ip, req.port))
+                .collect()),
+            None => Err(DnsLookupError::NoName),
+        };
+        self.responses.push_back(DnsLookupResponse {
+            address: req.address.clone(),
+            port: req.port,
+            result,
+        });
+        self.requests.push(req);
+        Ok(())
+    }
+
+    fn response_try_recv(&mut self) -> Result<DnsLookupResponse, ResponseTryRecvError> {
+        self.responses
+            .pop_front()
+            .ok_or(ResponseTryRecvError::Empty)
+    }
+}
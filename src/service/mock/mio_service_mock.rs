@@ -0,0 +1,178 @@
+use slab::Slab;
+use std::collections::{BTreeSet, VecDeque};
+use std::io::{self, Read, Write};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::event::P2pPeerEvent;
+use crate::peer::PeerToken;
+use crate::service::mio_service::{MioPeer, PeerConnectionIncomingAcceptError};
+use crate::service::MioService;
+
+/// In-memory peer stream, scripted by the test.
+#[derive(Debug, Default, Clone)]
+pub struct MockPeerStream {
+    /// Bytes that node will read from the stream.
+    pub incoming: VecDeque<u8>,
+    /// Bytes written by the node to the stream.
+    pub outgoing: Vec<u8>,
+}
+
+impl MockPeerStream {
+    pub fn push_incoming(&mut self, bytes: &[u8]) {
+        self.incoming.extend(bytes);
+    }
+
+    pub fn take_outgoing(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.outgoing)
+    }
+}
+
+impl Read for MockPeerStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.incoming.is_empty() {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        let len = buf.len().min(self.incoming.len());
+        for (dst, src) in buf.iter_mut().zip(self.incoming.drain(..len)) {
+            *dst = src;
+        }
+        Ok(len)
+    }
+}
+
+impl Write for MockPeerStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.outgoing.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// [MioService] whose peers are in-memory [MockPeerStream]s.
+///
+/// No events are produced by the service itself. Test dispatches
+/// them, with the help of [MioServiceMock::peer_event].
+#[derive(Default)]
+pub struct MioServiceMock {
+    listening: bool,
+    /// Incoming connections, which are waiting to be accepted.
+    backlog: VecDeque<SocketAddr>,
+    peers: Slab<MioPeer<MockPeerStream>>,
+
+    /// Outgoing connection to these addresses will be refused.
+    pub unreachable: BTreeSet<SocketAddr>,
+}
+
+impl MioServiceMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_listening(&self) -> bool {
+        self.listening
+    }
+
+    /// Queue incoming connection. It will be accepted once node
+    /// receives `P2pServerEvent`.
+    pub fn incoming_connection_add(&mut self, address: SocketAddr) {
+        self.backlog.push_back(address);
+    }
+
+    pub fn peer_token(&self, address: SocketAddr) -> Option<PeerToken> {
+        self.peers
+            .iter()
+            .find(|(_, peer)| peer.address == address)
+            .map(|(index, _)| PeerToken::new_unchecked(index))
+    }
+
+    pub fn peer_stream(&mut self, address: SocketAddr) -> Option<&mut MockPeerStream> {
+        self.peers
+            .iter_mut()
+            .find(|(_, peer)| peer.address == address)
+            .map(|(_, peer)| &mut peer.stream)
+    }
+
+    pub fn peers_len(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn peer_addresses(&self) -> Vec<SocketAddr> {
+        self.peers.iter().map(|(_, peer)| peer.address).collect()
+    }
+
+    /// Event for the connected peer with the given address.
+    pub fn peer_event(
+        &self,
+        address: SocketAddr,
+        is_readable: bool,
+        is_writable: bool,
+        is_closed: bool,
+    ) -> Option<P2pPeerEvent> {
+        Some(P2pPeerEvent {
+            token: self.peer_token(address)?,
+            address,
+            is_readable,
+            is_writable,
+            is_closed,
+        })
+    }
+
+    fn peer_add(&mut self, address: SocketAddr) -> PeerToken {
+        let index = self
+            .peers
+            .insert(MioPeer::new(address, MockPeerStream::default()));
+        PeerToken::new_unchecked(index)
+    }
+}
+
+impl MioService for MioServiceMock {
+    type PeerStream = MockPeerStream;
+    type Events = ();
+
+    fn wait_for_events(&mut self, _: &mut Self::Events, _: Option<Duration>) {}
+
+    fn peer_connection_incoming_listen_start(&mut self) -> io::Result<()> {
+        self.listening = true;
+        Ok(())
+    }
+
+    fn peer_connection_incoming_listen_stop(&mut self) {
+        self.listening = false;
+    }
+
+    fn peer_connection_incoming_accept(
+        &mut self,
+    ) -> Result<(PeerToken, &mut MioPeer<Self::PeerStream>), PeerConnectionIncomingAcceptError>
+    {
+        if !self.listening {
+            return Err(PeerConnectionIncomingAcceptError::ServerNotListening);
+        }
+        let address = self
+            .backlog
+            .pop_front()
+            .ok_or(PeerConnectionIncomingAcceptError::WouldBlock)?;
+        let token = self.peer_add(address);
+        Ok((token, &mut self.peers[token.index()]))
+    }
+
+    fn peer_connection_init(&mut self, address: SocketAddr) -> io::Result<PeerToken> {
+        if self.unreachable.contains(&address) {
+            return Err(io::ErrorKind::ConnectionRefused.into());
+        }
+        Ok(self.peer_add(address))
+    }
+
+    fn peer_disconnect(&mut self, token: PeerToken) {
+        if self.peers.contains(token.index()) {
+            self.peers.remove(token.index());
+        }
+    }
+
+    fn peer_get(&mut self, token: PeerToken) -> Option<&mut MioPeer<Self::PeerStream>> {
+        self.peers.get_mut(token.index())
+    }
+}
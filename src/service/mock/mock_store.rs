@@ -0,0 +1,141 @@
+//! Store over [super::ServiceMock] and helpers for driving the peer
+//! flows with `store.dispatch` in tests.
+
+use redux_rs::Store;
+use std::net::SocketAddr;
+
+use tezos_identity::Identity;
+
+use crate::action::Action;
+use crate::config::{test_config, Config, TezosNetwork};
+use crate::event::P2pServerEvent;
+use crate::peer::PeerStatus;
+use crate::peers::add::multi::PeersAddMultiAction;
+use crate::service::{MioService, Service, TimeService};
+use crate::shell_compatibility_version::ShellCompatibilityVersion;
+use crate::tick::TickAction;
+use crate::{effects, reducer, State};
+
+use super::ServiceMock;
+
+pub type MockStore = Store<State, ServiceMock, Action>;
+
+pub fn address(last_octet: u8) -> SocketAddr {
+    ([10, 0, 0, last_octet], 9732).into()
+}
+
+pub fn node_with_config(config: Config) -> MockStore {
+    let mut store = Store::new(reducer, ServiceMock::new(), State::new(config));
+    store.add_middleware(effects);
+
+    let time = store.service().time().now();
+    store.dispatch(TickAction { time }.into());
+
+    store
+        .service()
+        .mio()
+        .peer_connection_incoming_listen_start()
+        .unwrap();
+    store
+}
+
+pub fn node(address: SocketAddr) -> MockStore {
+    node_with_config(Config {
+        p2p_address: address,
+        identity: Identity::generate(0.0).unwrap(),
+        ..test_config()
+    })
+}
+
+/// Node on the test network, which supports only given p2p versions.
+pub fn node_with_p2p_versions(address: SocketAddr, p2p_versions: Vec<u16>) -> MockStore {
+    let chain_name = TezosNetwork::Granadanet
+        .shell_compatibility_version()
+        .to_network_version()
+        .chain_name()
+        .clone();
+    node_with_config(Config {
+        p2p_address: address,
+        identity: Identity::generate(0.0).unwrap(),
+        shell_compatibility_version: ShellCompatibilityVersion::new(
+            chain_name,
+            vec![0],
+            p2p_versions,
+        ),
+        ..test_config()
+    })
+}
+
+pub fn peer_status(store: &MockStore, address: SocketAddr) -> Option<&PeerStatus> {
+    store.state().peers.get(&address).map(|peer| &peer.status)
+}
+
+pub fn dispatch_peer_event(
+    store: &mut MockStore,
+    address: SocketAddr,
+    is_readable: bool,
+    is_writable: bool,
+    is_closed: bool,
+) {
+    let event = store
+        .service
+        .mio
+        .peer_event(address, is_readable, is_writable, is_closed)
+        .expect("peer not found in mio");
+    store.dispatch(event.into());
+}
+
+/// Move bytes written by `from` node into `to` node's stream.
+///
+/// Returns `false` if there was nothing to move.
+pub fn pipe(
+    from: &mut MockStore,
+    from_address: SocketAddr,
+    to: &mut MockStore,
+    to_address: SocketAddr,
+) -> bool {
+    let bytes = match from.service.mio.peer_stream(to_address) {
+        Some(stream) => stream.take_outgoing(),
+        None => return false,
+    };
+    if bytes.is_empty() {
+        return false;
+    }
+    match to.service.mio.peer_stream(from_address) {
+        Some(stream) => stream.push_incoming(&bytes),
+        None => return false,
+    }
+    dispatch_peer_event(to, from_address, true, false, false);
+    true
+}
+
+/// Move bytes between the nodes, until neither has anything to send.
+pub fn pipe_all(
+    a: &mut MockStore,
+    a_address: SocketAddr,
+    b: &mut MockStore,
+    b_address: SocketAddr,
+) {
+    for _ in 0..100 {
+        let a_to_b = pipe(a, a_address, b, b_address);
+        let b_to_a = pipe(b, b_address, a, a_address);
+        if !a_to_b && !b_to_a {
+            break;
+        }
+    }
+}
+
+/// Make node `a` connect to node `b`.
+pub fn connect(a: &mut MockStore, a_address: SocketAddr, b: &mut MockStore, b_address: SocketAddr) {
+    a.dispatch(
+        PeersAddMultiAction {
+            addresses: vec![b_address],
+        }
+        .into(),
+    );
+    b.service.mio.incoming_connection_add(a_address);
+    b.dispatch(P2pServerEvent {}.into());
+
+    dispatch_peer_event(a, b_address, false, true, false);
+    dispatch_peer_event(b, a_address, false, true, false);
+}
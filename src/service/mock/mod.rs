@@ -0,0 +1,26 @@
+//! In-memory implementations of the services, used for driving whole
+//! flows with `store.dispatch` in tests, without touching network,
+//! disk or the clock.
+
+mod time_service_mock;
+pub use time_service_mock::*;
+
+mod dns_service_mock;
+pub use dns_service_mock::*;
+
+mod mio_service_mock;
+pub use mio_service_mock::*;
+
+mod storage_service_mock;
+pub use storage_service_mock::*;
+
+mod rpc_service_mock;
+pub use rpc_service_mock::*;
+
+mod service_mock;
+pub use service_mock::*;
+
+#[cfg(test)]
+mod mock_store;
+#[cfg(test)]
+pub use mock_store::*;
@@ -0,0 +1,25 @@
+use std::collections::VecDeque;
+
+use crate::service::rpc_service::RpcResponse;
+use crate::service::service_channel::ResponseTryRecvError;
+use crate::service::RpcService;
+
+#[derive(Debug, Default)]
+pub struct RpcServiceMock {
+    /// Rpc requests which node will receive.
+    pub responses: VecDeque<RpcResponse>,
+}
+
+impl RpcServiceMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RpcService for RpcServiceMock {
+    fn try_recv(&mut self) -> Result<RpcResponse, ResponseTryRecvError> {
+        self.responses
+            .pop_front()
+            .ok_or(ResponseTryRecvError::Empty)
+    }
+}
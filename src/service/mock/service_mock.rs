@@ -0,0 +1,64 @@
+use crate::service::{RandomnessServiceDefault, Service};
+
+use super::{DnsServiceMock, MioServiceMock, RpcServiceMock, StorageServiceMock, TimeServiceMock};
+
+pub struct ServiceMock {
+    pub time: TimeServiceMock,
+    pub randomness: RandomnessServiceDefault,
+    pub dns: DnsServiceMock,
+    pub mio: MioServiceMock,
+    pub storage: StorageServiceMock,
+    pub rpc: RpcServiceMock,
+}
+
+impl ServiceMock {
+    pub fn new() -> Self {
+        Self {
+            time: TimeServiceMock::new(),
+            randomness: RandomnessServiceDefault::new(Some(0)),
+            dns: DnsServiceMock::new(),
+            mio: MioServiceMock::new(),
+            storage: StorageServiceMock::new(),
+            rpc: RpcServiceMock::new(),
+        }
+    }
+}
+
+impl Default for ServiceMock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Service for ServiceMock {
+    type Time = TimeServiceMock;
+    type Randomness = RandomnessServiceDefault;
+    type Dns = DnsServiceMock;
+    type Mio = MioServiceMock;
+    type Storage = StorageServiceMock;
+    type Rpc = RpcServiceMock;
+
+    fn time(&mut self) -> &mut Self::Time {
+        &mut self.time
+    }
+
+    fn randomness(&mut self) -> &mut Self::Randomness {
+        &mut self.randomness
+    }
+
+    fn dns(&mut self) -> &mut Self::Dns {
+        &mut self.dns
+    }
+
+    fn mio(&mut self) -> &mut Self::Mio {
+        &mut self.mio
+    }
+
+    fn storage(&mut self) -> &mut Self::Storage {
+        &mut self.storage
+    }
+
+    fn rpc(&mut self) -> &mut Self::Rpc {
+        &mut self.rpc
+    }
+}
@@ -0,0 +1,39 @@
+use std::collections::VecDeque;
+
+use crate::service::service_channel::{RequestSendError, ResponseTryRecvError};
+use crate::service::storage_service::{StorageRequest, StorageRequestPayload, StorageResponse};
+use crate::service::StorageService;
+
+#[derive(Debug, Default)]
+pub struct StorageServiceMock {
+    /// Requests sent to the storage.
+    ///
+    /// `ActionPut` requests aren't recorded, as one is sent for every action.
+    pub requests: Vec<StorageRequest>,
+    /// Responses which node will receive.
+    pub responses: VecDeque<StorageResponse>,
+}
+
+impl StorageServiceMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StorageService for StorageServiceMock {
+    fn request_send(
+        &mut self,
+        req: StorageRequest,
+    ) -> Result<(), RequestSendError<StorageRequest>> {
+        if !matches!(&req.payload, StorageRequestPayload::ActionPut(_)) {
+            self.requests.push(req);
+        }
+        Ok(())
+    }
+
+    fn response_try_recv(&mut self) -> Result<StorageResponse, ResponseTryRecvError> {
+        self.responses
+            .pop_front()
+            .ok_or(ResponseTryRecvError::Empty)
+    }
+}
@@ -0,0 +1,33 @@
+use std::time::{Duration, SystemTime};
+
+use crate::service::TimeService;
+
+/// Time only moves when it's advanced manually.
+#[derive(Debug, Clone)]
+pub struct TimeServiceMock {
+    pub time: SystemTime,
+}
+
+impl TimeServiceMock {
+    pub fn new() -> Self {
+        Self {
+            time: SystemTime::UNIX_EPOCH,
+        }
+    }
+
+    pub fn advance(&mut self, duration: Duration) {
+        self.time += duration;
+    }
+}
+
+impl Default for TimeServiceMock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeService for TimeServiceMock {
+    fn now(&mut self) -> SystemTime {
+        self.time
+    }
+}
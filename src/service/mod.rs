@@ -1,5 +1,8 @@
 pub mod service_channel;
 
+pub mod time_service;
+pub use time_service::{TimeService, TimeServiceDefault};
+
 pub mod dns_service;
 pub use dns_service::{DnsService, DnsServiceDefault};
 
@@ -15,13 +18,19 @@ pub use storage_service::{StorageService, StorageServiceDefault};
 pub mod rpc_service;
 pub use rpc_service::{RpcService, RpcServiceDefault};
 
+#[cfg(test)]
+pub mod mock;
+
 pub trait Service {
+    type Time: TimeService;
     type Randomness: RandomnessService;
     type Dns: DnsService;
     type Mio: MioService;
     type Storage: StorageService;
     type Rpc: RpcService;
 
+    fn time(&mut self) -> &mut Self::Time;
+
     fn randomness(&mut self) -> &mut Self::Randomness;
 
     fn dns(&mut self) -> &mut Self::Dns;
@@ -34,6 +43,7 @@ pub trait Service {
 }
 
 pub struct ServiceDefault {
+    pub time: TimeServiceDefault,
     pub randomness: RandomnessServiceDefault,
     pub dns: DnsServiceDefault,
     pub mio: MioServiceDefault,
@@ -42,12 +52,17 @@ pub struct ServiceDefault {
 }
 
 impl Service for ServiceDefault {
+    type Time = TimeServiceDefault;
     type Randomness = RandomnessServiceDefault;
     type Dns = DnsServiceDefault;
     type Mio = MioServiceDefault;
     type Storage = StorageServiceDefault;
     type Rpc = RpcServiceDefault;
 
+    fn time(&mut self) -> &mut Self::Time {
+        &mut self.time
+    }
+
     fn randomness(&mut self) -> &mut Self::Randomness {
         &mut self.randomness
     }
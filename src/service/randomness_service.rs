@@ -1,16 +1,71 @@
 use std::{fmt::Debug, net::SocketAddr};
 
 use crypto::nonce::Nonce;
+use rand::rngs::ThreadRng;
 use rand::seq::SliceRandom;
-use rand::Rng;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
-pub type RandomnessServiceDefault = rand::rngs::ThreadRng;
+use crate::peer::score::PeerScore;
+
+/// OS seeded by default. With `config.randomness_seed` set, it's
+/// deterministic instead, so that the same seed produces the same
+/// nonces and peer choices.
+#[derive(Debug)]
+pub enum RandomnessServiceDefault {
+    Os(ThreadRng),
+    Seeded(ChaCha8Rng),
+}
+
+impl RandomnessServiceDefault {
+    pub fn new(seed: Option<u64>) -> Self {
+        match seed {
+            Some(seed) => Self::Seeded(ChaCha8Rng::seed_from_u64(seed)),
+            None => Self::Os(rand::thread_rng()),
+        }
+    }
+}
+
+impl RngCore for RandomnessServiceDefault {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Self::Os(rng) => rng.next_u32(),
+            Self::Seeded(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Self::Os(rng) => rng.next_u64(),
+            Self::Seeded(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Self::Os(rng) => rng.fill_bytes(dest),
+            Self::Seeded(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match self {
+            Self::Os(rng) => rng.try_fill_bytes(dest),
+            Self::Seeded(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
 
 pub trait RandomnessService {
     fn get_nonce(&mut self, peer: SocketAddr) -> Nonce;
 
     /// Choose peer to initiate random outgoing connection.
-    fn choose_peer(&mut self, list: &[SocketAddr]) -> Option<SocketAddr>;
+    ///
+    /// Peers with higher score are more likely to be chosen.
+    fn choose_peer(&mut self, list: &[(SocketAddr, PeerScore)]) -> Option<SocketAddr>;
+
+    /// Choose at most `amount` distinct peers from the list, in random order.
+    fn choose_peers(&mut self, list: &[SocketAddr], amount: usize) -> Vec<SocketAddr>;
 }
 
 impl<R> RandomnessService for R
@@ -23,7 +78,37 @@ where
         Nonce::new(&b)
     }
 
-    fn choose_peer(&mut self, list: &[SocketAddr]) -> Option<SocketAddr> {
-        list.choose(self).cloned()
+    fn choose_peer(&mut self, list: &[(SocketAddr, PeerScore)]) -> Option<SocketAddr> {
+        // negative scores get the same, smallest weight, so that
+        // those peers still have a chance to be chosen.
+        list.choose_weighted(self, |(_, score)| 1 + (*score).max(0) as u32)
+            .ok()
+            .map(|(address, _)| *address)
+    }
+
+    fn choose_peers(&mut self, list: &[SocketAddr], amount: usize) -> Vec<SocketAddr> {
+        list.choose_multiple(self, amount).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose_peer_weighted_by_score() {
+        let mut rng = RandomnessServiceDefault::new(Some(0));
+        let good: SocketAddr = ([10, 0, 0, 1], 9732).into();
+        let bad: SocketAddr = ([10, 0, 0, 2], 9732).into();
+        let list = [(good, 99), (bad, -50)];
+
+        let good_chosen = (0..1000)
+            .filter(|_| rng.choose_peer(&list) == Some(good))
+            .count();
+        // weights are 100 and 1.
+        assert!(good_chosen > 950, "good peer chosen {} times", good_chosen);
+        assert!(good_chosen < 1000, "bad peer never chosen");
+
+        assert_eq!(rng.choose_peer(&[]), None);
     }
 }
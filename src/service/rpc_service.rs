@@ -11,6 +11,7 @@ use std::sync::Arc;
 use std::thread;
 use storage::{PersistentStorage, ReduxActionStorage, ReduxStateStorage, StorageError};
 
+use crate::chain::ChainPeerCurrentHead;
 use crate::{action::Action, State};
 
 use super::service_channel::{
@@ -37,6 +38,13 @@ pub struct RpcServiceDefault {
     worker_channel: ServiceWorkerRequester<(), RpcResponse>,
 }
 
+#[derive(Serialize)]
+struct ChainPeerAhead<'a> {
+    address: &'a SocketAddr,
+    #[serde(flatten)]
+    head: &'a ChainPeerCurrentHead,
+}
+
 #[derive(Serialize, Deserialize)]
 struct ActionWithState {
     #[serde(flatten)]
@@ -120,9 +128,10 @@ impl RpcServiceDefault {
     async fn get_state_before_action_id(
         snapshot_storage: &ReduxStateStorage,
         action_storage: &ReduxActionStorage,
+        snapshot_interval: u64,
         target_action_id: u64,
     ) -> Result<State, Box<dyn std::error::Error>> {
-        let closest_snapshot_action_id = target_action_id / 10000;
+        let closest_snapshot_action_id = target_action_id - target_action_id % snapshot_interval;
         let mut state = match snapshot_storage.get(&closest_snapshot_action_id).unwrap() {
             Some(v) => v,
             None => return Err("snapshot not available".into()),
@@ -144,11 +153,16 @@ impl RpcServiceDefault {
     async fn get_state_after_action_id(
         snapshot_storage: &ReduxStateStorage,
         action_storage: &ReduxActionStorage,
+        snapshot_interval: u64,
         target_action_id: u64,
     ) -> Result<State, Box<dyn std::error::Error>> {
-        let mut state =
-            Self::get_state_before_action_id(snapshot_storage, action_storage, target_action_id)
-                .await?;
+        let mut state = Self::get_state_before_action_id(
+            snapshot_storage,
+            action_storage,
+            snapshot_interval,
+            target_action_id,
+        )
+        .await?;
 
         if let Some(action) = Self::get_action(action_storage, target_action_id)
             .await
@@ -164,22 +178,40 @@ impl RpcServiceDefault {
         sender: ServiceWorkerResponderSender<RpcResponse>,
         snapshot_storage: &ReduxStateStorage,
         action_storage: &ReduxActionStorage,
+        snapshot_interval: u64,
         target_action_id: Option<u64>,
     ) -> ServiceResult {
         make_json_response(&match target_action_id {
-            Some(target_action_id) => {
-                Self::get_state_after_action_id(snapshot_storage, action_storage, target_action_id)
-                    .await
-                    .ok()
-            }
+            Some(target_action_id) => Self::get_state_after_action_id(
+                snapshot_storage,
+                action_storage,
+                snapshot_interval,
+                target_action_id,
+            )
+            .await
+            .ok(),
             None => Some(Self::get_current_global_state(sender).await.unwrap()),
         })
     }
 
+    async fn handle_chain_peers_ahead_get(
+        sender: ServiceWorkerResponderSender<RpcResponse>,
+    ) -> ServiceResult {
+        let state = Self::get_current_global_state(sender).await?;
+        let peers_ahead = state
+            .chain
+            .peers_ahead()
+            .map(|(address, head)| ChainPeerAhead { address, head })
+            .collect::<Vec<_>>();
+
+        make_json_response(&peers_ahead)
+    }
+
     async fn handle_actions_get(
         sender: ServiceWorkerResponderSender<RpcResponse>,
         snapshot_storage: &ReduxStateStorage,
         action_storage: &ReduxActionStorage,
+        snapshot_interval: u64,
         cursor: Option<u64>,
         limit: Option<u64>,
     ) -> ServiceResult {
@@ -195,14 +227,20 @@ impl RpcServiceDefault {
         };
         let start = end.checked_sub(limit - 1).unwrap_or(0);
 
-        let mut state =
-            match Self::get_state_before_action_id(snapshot_storage, action_storage, start).await {
-                Ok(v) => v,
-                Err(err) => {
-                    dbg!(err);
-                    return make_json_response::<Vec<()>>(&vec![]);
-                }
-            };
+        let mut state = match Self::get_state_before_action_id(
+            snapshot_storage,
+            action_storage,
+            snapshot_interval,
+            start,
+        )
+        .await
+        {
+            Ok(v) => v,
+            Err(err) => {
+                dbg!(err);
+                return make_json_response::<Vec<()>>(&vec![]);
+            }
+        };
 
         let mut actions_with_state = VecDeque::new();
 
@@ -229,6 +267,7 @@ impl RpcServiceDefault {
 
     fn run_worker(
         bind_address: SocketAddr,
+        snapshot_interval: u64,
         channel: ServiceWorkerResponder<(), RpcResponse>,
         storage: PersistentStorage,
     ) -> impl Future<Output = Result<(), hyper::Error>> {
@@ -260,6 +299,7 @@ impl RpcServiceDefault {
                                 sender,
                                 &snapshot_storage,
                                 &action_storage,
+                                snapshot_interval,
                                 query.get("action_id").map(|x| x[0].parse().ok()).flatten(),
                             )
                             .await
@@ -274,10 +314,13 @@ impl RpcServiceDefault {
                                 sender,
                                 &snapshot_storage,
                                 &action_storage,
+                                snapshot_interval,
                                 query.get("cursor").map(|x| x[0].parse().ok()).flatten(),
                                 query.get("limit").map(|x| x[0].parse().ok()).flatten(),
                             )
                             .await
+                        } else if path == "/chain/peers_ahead" {
+                            Self::handle_chain_peers_ahead_get(sender).await
                         } else {
                             not_found()
                         }
@@ -288,14 +331,18 @@ impl RpcServiceDefault {
     }
 
     // TODO: remove unwraps
-    pub fn init(waker: Arc<mio::Waker>, storage: PersistentStorage) -> Self {
+    pub fn init(
+        waker: Arc<mio::Waker>,
+        rpc_listen_address: SocketAddr,
+        snapshot_interval: u64,
+        storage: PersistentStorage,
+    ) -> Self {
         let (requester, responder) = worker_channel(waker);
 
         thread::spawn(move || {
-            let rpc_listen_address = ([0, 0, 0, 0], 18732).into();
             let threaded_rt = tokio::runtime::Runtime::new().unwrap();
             threaded_rt.block_on(async move {
-                Self::run_worker(rpc_listen_address, responder, storage)
+                Self::run_worker(rpc_listen_address, snapshot_interval, responder, storage)
                     .await
                     .unwrap();
             });
@@ -2,6 +2,7 @@ use redux_rs::{ActionId, ActionWithId};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::thread;
+use std::time::SystemTime;
 
 use storage::{
     BlockHeaderWithHash, BlockStorage, PersistentStorage, ReduxActionStorage, ReduxStateStorage,
@@ -10,6 +11,8 @@ use storage::{
 
 use crate::action::Action;
 use crate::request::RequestId;
+use crate::storage::peers::StoredPeer;
+use crate::tmp::peers_storage::{PeersStorage, PeersStorageError};
 use crate::State;
 
 use super::service_channel::{
@@ -38,10 +41,21 @@ impl From<StorageError> for StorageErrorTmp {
     }
 }
 
+impl From<PeersStorageError> for StorageErrorTmp {
+    fn from(_: PeersStorageError) -> Self {
+        Self {}
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum StorageRequestPayload {
     BlockHeaderWithHashPut(BlockHeaderWithHash),
 
+    PeersGet,
+    /// Stored peers, which weren't seen for a while since the given
+    /// time, are pruned.
+    PeersPut(Vec<StoredPeer>, SystemTime),
+
     StateSnapshotPut(Arc<State>),
     ActionPut(Box<ActionWithId<Action>>),
 }
@@ -50,6 +64,9 @@ pub enum StorageRequestPayload {
 pub enum StorageResponseSuccess {
     BlockHeaderWithHashPutSuccess(bool),
 
+    PeersGetSuccess(Vec<StoredPeer>),
+    PeersPutSuccess,
+
     StateSnapshotPutSuccess(ActionId),
     ActionPutSuccess(ActionId),
 }
@@ -58,6 +75,9 @@ pub enum StorageResponseSuccess {
 pub enum StorageResponseError {
     BlockHeaderWithHashPutError(StorageErrorTmp),
 
+    PeersGetError(StorageErrorTmp),
+    PeersPutError(StorageErrorTmp),
+
     StateSnapshotPutError(StorageErrorTmp),
     ActionPutError(StorageErrorTmp),
 }
@@ -98,7 +118,11 @@ pub struct StorageServiceDefault {
 }
 
 impl StorageServiceDefault {
-    fn run_worker(storage: PersistentStorage, mut channel: StorageWorkerResponder) {
+    fn run_worker(
+        storage: PersistentStorage,
+        peers_storage: PeersStorage,
+        mut channel: StorageWorkerResponder,
+    ) {
         use StorageRequestPayload::*;
         use StorageResponseError::*;
         use StorageResponseSuccess::*;
@@ -114,6 +138,15 @@ impl StorageServiceDefault {
                     .map(|res| BlockHeaderWithHashPutSuccess(res))
                     .map_err(|err| BlockHeaderWithHashPutError(err.into())),
 
+                PeersGet => peers_storage
+                    .get()
+                    .map(|peers| PeersGetSuccess(peers))
+                    .map_err(|err| PeersGetError(err.into())),
+                PeersPut(peers, time) => peers_storage
+                    .put(peers, time)
+                    .map(|_| PeersPutSuccess)
+                    .map_err(|err| PeersPutError(err.into())),
+
                 ActionPut(action) => action_storage
                     .put::<Action>(&action.id.into(), &action.action)
                     .map(|_| ActionPutSuccess(action.id))
@@ -134,12 +167,16 @@ impl StorageServiceDefault {
     }
 
     // TODO: remove unwraps
-    pub fn init(waker: Arc<mio::Waker>, persistent_storage: PersistentStorage) -> Self {
+    pub fn init(
+        waker: Arc<mio::Waker>,
+        persistent_storage: PersistentStorage,
+        peers_storage: PeersStorage,
+    ) -> Self {
         let (requester, responder) = worker_channel(waker);
 
         thread::Builder::new()
             .name("storage-thread".to_owned())
-            .spawn(move || Self::run_worker(persistent_storage, responder))
+            .spawn(move || Self::run_worker(persistent_storage, peers_storage, responder))
             .unwrap();
 
         Self {
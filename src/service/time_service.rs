@@ -0,0 +1,22 @@
+use std::time::SystemTime;
+
+pub trait TimeService {
+    /// Current time.
+    fn now(&mut self) -> SystemTime;
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct TimeServiceDefault;
+
+impl TimeServiceDefault {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl TimeService for TimeServiceDefault {
+    #[inline(always)]
+    fn now(&mut self) -> SystemTime {
+        SystemTime::now()
+    }
+}
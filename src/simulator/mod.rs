@@ -0,0 +1,7 @@
+//! In-process simulator of multiple nodes, connected over the virtual
+//! network and driven by the virtual clock.
+
+mod sim;
+pub use sim::*;
+
+mod simulator_tests;
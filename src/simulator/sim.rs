@@ -0,0 +1,365 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use redux_rs::Store;
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::SocketAddr;
+use std::time::{Duration, SystemTime};
+
+use crate::action::Action;
+use crate::config::Config;
+use crate::event::{P2pServerEvent, WakeupEvent};
+use crate::service::mock::ServiceMock;
+use crate::service::{MioService, RandomnessServiceDefault};
+use crate::tick::{TickAction, TICK_INTERVAL};
+use crate::{effects, reducer, State};
+
+pub type SimService = ServiceMock;
+pub type SimStore = Store<State, SimService, Action>;
+
+#[derive(Debug, Clone)]
+pub struct SimulatorConfig {
+    /// Virtual time that passes with each [Simulator::step].
+    pub step: Duration,
+    /// One way latency of the network.
+    pub latency: Duration,
+    /// Probability (0.0 - 1.0) that sent bytes get lost.
+    ///
+    /// Like in TCP, lost bytes aren't dropped, but retransmitted
+    /// after `retransmit_delay`.
+    pub loss: f64,
+    pub retransmit_delay: Duration,
+    pub seed: u64,
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        Self {
+            step: Duration::from_millis(10),
+            latency: Duration::from_millis(20),
+            loss: 0.0,
+            retransmit_delay: Duration::from_millis(200),
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum SimEventKind {
+    /// Outgoing connection from `from` reached listening node `to`.
+    Connect,
+    /// Bytes sent from `from` arrived at `to`.
+    Deliver(Vec<u8>),
+    /// Connection got closed by `from`.
+    Close,
+}
+
+#[derive(Debug, Clone)]
+struct SimEvent {
+    at: SystemTime,
+    from: SocketAddr,
+    to: SocketAddr,
+    kind: SimEventKind,
+}
+
+/// Simulates multiple nodes over the virtual network.
+///
+/// Each node is identified by its `config.p2p_address`. Connections
+/// between nodes are seen by both sides with each other's p2p address.
+pub struct Simulator {
+    config: SimulatorConfig,
+    time: SystemTime,
+    rng: ChaCha8Rng,
+
+    nodes: BTreeMap<SocketAddr, SimStore>,
+    /// Connections (initiator, target), whether established or not.
+    connections: BTreeSet<(SocketAddr, SocketAddr)>,
+    /// Connections which got established.
+    established: BTreeSet<(SocketAddr, SocketAddr)>,
+    /// Links (in both directions) which are cut by partition.
+    partitioned: BTreeSet<(SocketAddr, SocketAddr)>,
+
+    events: Vec<SimEvent>,
+    /// Delivery time of the last bytes sent over the link, used to
+    /// keep bytes ordered.
+    last_delivery: BTreeMap<(SocketAddr, SocketAddr), SystemTime>,
+}
+
+impl Simulator {
+    pub fn new(config: SimulatorConfig) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(config.seed),
+            config,
+            time: SystemTime::UNIX_EPOCH,
+            nodes: BTreeMap::new(),
+            connections: BTreeSet::new(),
+            established: BTreeSet::new(),
+            partitioned: BTreeSet::new(),
+            events: vec![],
+            last_delivery: BTreeMap::new(),
+        }
+    }
+
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// Add node with the given config. It will listen on `config.p2p_address`.
+    pub fn node_add(&mut self, config: Config) -> SocketAddr {
+        let address = config.p2p_address;
+        let mut service = SimService::new();
+        service.time.time = self.time;
+        service.randomness = RandomnessServiceDefault::new(config.randomness_seed);
+
+        let mut store = Store::new(reducer, service, State::new(config));
+        store.add_middleware(effects);
+        store.dispatch(TickAction { time: self.time }.into());
+        store
+            .service
+            .mio
+            .peer_connection_incoming_listen_start()
+            .unwrap();
+
+        self.nodes.insert(address, store);
+        address
+    }
+
+    pub fn node(&self, address: SocketAddr) -> &SimStore {
+        &self.nodes[&address]
+    }
+
+    pub fn node_mut(&mut self, address: SocketAddr) -> &mut SimStore {
+        self.nodes.get_mut(&address).unwrap()
+    }
+
+    pub fn dispatch(&mut self, address: SocketAddr, action: Action) {
+        self.node_mut(address).dispatch(action);
+    }
+
+    /// Cut the links between every node in `a` and every node in `b`.
+    pub fn partition(&mut self, a: &[SocketAddr], b: &[SocketAddr]) {
+        for a in a {
+            for b in b {
+                self.partitioned.insert((*a, *b));
+                self.partitioned.insert((*b, *a));
+            }
+        }
+    }
+
+    /// Remove all partitions.
+    pub fn heal(&mut self) {
+        self.partitioned.clear();
+    }
+
+    fn is_partitioned(&self, from: SocketAddr, to: SocketAddr) -> bool {
+        self.partitioned.contains(&(from, to))
+    }
+
+    fn event_add(&mut self, from: SocketAddr, to: SocketAddr, kind: SimEventKind) {
+        let mut at = self.time + self.config.latency;
+        if self.rng.gen_bool(self.config.loss) {
+            at += self.config.retransmit_delay;
+        }
+        let last_delivery = self.last_delivery.entry((from, to)).or_insert(at);
+        at = at.max(*last_delivery);
+        *last_delivery = at;
+
+        self.events.push(SimEvent { at, from, to, kind });
+    }
+
+    fn peer_event_dispatch(
+        &mut self,
+        node: SocketAddr,
+        peer: SocketAddr,
+        is_readable: bool,
+        is_writable: bool,
+        is_closed: bool,
+    ) {
+        let store = match self.nodes.get_mut(&node) {
+            Some(v) => v,
+            None => return,
+        };
+        if let Some(event) = store
+            .service
+            .mio
+            .peer_event(peer, is_readable, is_writable, is_closed)
+        {
+            store.dispatch(event.into());
+        }
+    }
+
+    /// Advance virtual time by `config.step` and move the network along.
+    pub fn step(&mut self) {
+        self.time += self.config.step;
+        let time = self.time;
+
+        for store in self.nodes.values_mut() {
+            store.service.time.time = time;
+            let since_last_tick = time.duration_since(store.state().time).unwrap_or_default();
+            if since_last_tick >= TICK_INTERVAL {
+                store.dispatch(TickAction { time }.into());
+            }
+            if !store.service.dns.responses.is_empty() {
+                store.dispatch(WakeupEvent {}.into());
+            }
+        }
+
+        self.connections_update();
+        self.bytes_send();
+        self.events_process();
+    }
+
+    /// Detect new outgoing connections and closed ones.
+    fn connections_update(&mut self) {
+        let node_peers = self
+            .nodes
+            .iter()
+            .map(|(address, store)| (*address, store.service.mio.peer_addresses()))
+            .collect::<Vec<_>>();
+        let has_peer = |node: SocketAddr, peer: SocketAddr| {
+            node_peers
+                .iter()
+                .any(|(address, peers)| *address == node && peers.contains(&peer))
+        };
+
+        for (node, peers) in &node_peers {
+            for peer in peers {
+                if self.connections.contains(&(*node, *peer))
+                    || self.connections.contains(&(*peer, *node))
+                {
+                    continue;
+                }
+                self.connections.insert((*node, *peer));
+
+                let is_listening = self
+                    .nodes
+                    .get(peer)
+                    .map_or(false, |store| store.service.mio.is_listening());
+                if !is_listening {
+                    // connection refused.
+                    self.event_add(*peer, *node, SimEventKind::Close);
+                } else if !self.is_partitioned(*node, *peer) {
+                    self.event_add(*node, *peer, SimEventKind::Connect);
+                }
+                // if link is partitioned, connection just hangs.
+            }
+        }
+
+        let closed = self
+            .connections
+            .iter()
+            .filter(|(a, b)| {
+                if self.established.contains(&(*a, *b)) {
+                    !has_peer(*a, *b) || !has_peer(*b, *a)
+                } else {
+                    !has_peer(*a, *b)
+                }
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for (a, b) in closed {
+            self.connections.remove(&(a, b));
+            if self.established.remove(&(a, b)) {
+                // notify the side which still has the connection.
+                if has_peer(a, b) {
+                    self.event_add(b, a, SimEventKind::Close);
+                } else if has_peer(b, a) {
+                    self.event_add(a, b, SimEventKind::Close);
+                }
+            }
+        }
+    }
+
+    /// Send bytes written by nodes to the network.
+    fn bytes_send(&mut self) {
+        let links = self
+            .established
+            .iter()
+            .flat_map(|(a, b)| vec![(*a, *b), (*b, *a)])
+            .filter(|(from, to)| !self.is_partitioned(*from, *to))
+            .collect::<Vec<_>>();
+
+        for (from, to) in links {
+            let bytes = match self.nodes.get_mut(&from) {
+                Some(store) => match store.service.mio.peer_stream(to) {
+                    Some(stream) => stream.take_outgoing(),
+                    None => continue,
+                },
+                None => continue,
+            };
+            if !bytes.is_empty() {
+                self.event_add(from, to, SimEventKind::Deliver(bytes));
+            }
+        }
+    }
+
+    fn events_process(&mut self) {
+        let time = self.time;
+        let (due, pending) = std::mem::take(&mut self.events)
+            .into_iter()
+            .partition::<Vec<_>, _>(|event| event.at <= time);
+        self.events = pending;
+
+        let mut due = due;
+        due.sort_by_key(|event| event.at);
+
+        for event in due {
+            let (from, to) = (event.from, event.to);
+            match event.kind {
+                SimEventKind::Connect => {
+                    if !self.connections.contains(&(from, to)) {
+                        continue;
+                    }
+                    self.established.insert((from, to));
+                    let target = self.node_mut(to);
+                    target.service.mio.incoming_connection_add(from);
+                    target.dispatch(P2pServerEvent {}.into());
+
+                    self.peer_event_dispatch(from, to, false, true, false);
+                    self.peer_event_dispatch(to, from, false, true, false);
+                }
+                SimEventKind::Deliver(bytes) => {
+                    let stream = self
+                        .nodes
+                        .get_mut(&to)
+                        .and_then(|store| store.service.mio.peer_stream(from));
+                    if let Some(stream) = stream {
+                        stream.push_incoming(&bytes);
+                        self.peer_event_dispatch(to, from, true, false, false);
+                    }
+                }
+                SimEventKind::Close => {
+                    self.peer_event_dispatch(to, from, false, false, true);
+                    // socket is closed, so remove it from mio as well.
+                    if let Some(store) = self.nodes.get_mut(&to) {
+                        if let Some(token) = store.service.mio.peer_token(from) {
+                            store.service.mio.peer_disconnect(token);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn run_for(&mut self, duration: Duration) {
+        let until = self.time + duration;
+        while self.time < until {
+            self.step();
+        }
+    }
+
+    /// Run until predicate is satisfied, or `timeout` of the virtual
+    /// time passes. Returns whether predicate was satisfied.
+    pub fn run_until<F>(&mut self, timeout: Duration, mut predicate: F) -> bool
+    where
+        F: FnMut(&Self) -> bool,
+    {
+        let until = self.time + timeout;
+        while self.time < until {
+            if predicate(self) {
+                return true;
+            }
+            self.step();
+        }
+        predicate(self)
+    }
+}
@@ -0,0 +1,506 @@
+//! Scenarios of peer management, handshake and chain logic over the
+//! simulated network.
+
+use std::convert::TryFrom;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crypto::hash::{BlockHash, ChainId, ContextHash, OperationListListHash};
+use storage::BlockHeaderWithHash;
+use tezos_identity::Identity;
+use tezos_messages::p2p::encoding::block_header::{BlockHeaderBuilder, BlockHeaderMessage};
+use tezos_messages::p2p::encoding::current_head::{CurrentHeadMessage, GetCurrentHeadMessage};
+use tezos_messages::p2p::encoding::mempool::Mempool;
+use tezos_messages::p2p::encoding::peer::PeerMessage;
+
+use crate::chain::block_header_with_hash;
+use crate::config::{test_config, Config, TezosNetwork};
+use crate::io_error_kind::IOErrorKind;
+use crate::peer::disconnection::PeerDisconnectAction;
+use crate::peer::message::write::PeerMessageWriteInitAction;
+use crate::peer::PeerStatus;
+use crate::peers::add::multi::PeersAddMultiAction;
+use crate::peers::dns_lookup::{PeersDnsLookupInitAction, PeersDnsLookupStatus};
+
+use super::{Simulator, SimulatorConfig};
+
+fn node_config(id: u8) -> Config {
+    Config {
+        p2p_address: ([10, 0, 0, id], 9732).into(),
+        identity: Identity::generate(0.0).unwrap(),
+        randomness_seed: Some(id as u64),
+        ..test_config()
+    }
+}
+
+fn peer_status(sim: &Simulator, node: SocketAddr, peer: SocketAddr) -> Option<&PeerStatus> {
+    sim.node(node)
+        .state()
+        .peers
+        .get(&peer)
+        .map(|peer| &peer.status)
+}
+
+fn is_handshaked(sim: &Simulator, node: SocketAddr, peer: SocketAddr) -> bool {
+    matches!(
+        peer_status(sim, node, peer),
+        Some(PeerStatus::Handshaked(_))
+    )
+}
+
+fn connect(sim: &mut Simulator, node: SocketAddr, peer: SocketAddr) {
+    sim.dispatch(
+        node,
+        PeersAddMultiAction {
+            addresses: vec![peer],
+        }
+        .into(),
+    );
+}
+
+#[test]
+fn test_handshake() {
+    let mut sim = Simulator::new(SimulatorConfig::default());
+    let a = sim.node_add(node_config(1));
+    let b = sim.node_add(node_config(2));
+
+    connect(&mut sim, a, b);
+
+    assert!(sim.run_until(Duration::from_secs(5), |sim| {
+        is_handshaked(sim, a, b) && is_handshaked(sim, b, a)
+    }));
+}
+
+#[test]
+fn test_handshake_with_loss() {
+    let mut sim = Simulator::new(SimulatorConfig {
+        loss: 0.3,
+        ..SimulatorConfig::default()
+    });
+    let a = sim.node_add(node_config(1));
+    let b = sim.node_add(node_config(2));
+
+    connect(&mut sim, a, b);
+
+    assert!(sim.run_until(Duration::from_secs(10), |sim| {
+        is_handshaked(sim, a, b) && is_handshaked(sim, b, a)
+    }));
+}
+
+/// Node A bootstraps (using dns) from B and C, while C Nacks it,
+/// because it's on the different network.
+#[test]
+fn test_bootstrap_while_nacked() {
+    let mut sim = Simulator::new(SimulatorConfig::default());
+    let a = sim.node_add(node_config(1));
+    let b = sim.node_add(node_config(2));
+    let c = sim.node_add(Config {
+        shell_compatibility_version: TezosNetwork::Mainnet.shell_compatibility_version(),
+        ..node_config(3)
+    });
+
+    sim.node_mut(a)
+        .service
+        .dns
+        .records
+        .insert("boot.sim".to_owned(), vec![b.ip(), c.ip()]);
+    sim.dispatch(
+        a,
+        PeersDnsLookupInitAction {
+            address: "boot.sim".to_owned(),
+            port: 9732,
+        }
+        .into(),
+    );
+
+    assert!(sim.run_until(Duration::from_secs(5), |sim| {
+        is_handshaked(sim, a, b) && is_handshaked(sim, b, a)
+    }));
+    sim.run_for(Duration::from_secs(2));
+
+    assert!(!is_handshaked(&sim, a, c));
+    assert!(sim.node(c).state().peers.is_empty());
+}
+
+/// Failed lookup of the configured bootstrap dns name is retried.
+#[test]
+fn test_bootstrap_dns_lookup_retry() {
+    let mut sim = Simulator::new(SimulatorConfig::default());
+    let a = sim.node_add(Config {
+        bootstrap_peers: vec![("boot.sim".to_owned(), 9732)],
+        ..node_config(1)
+    });
+    let b = sim.node_add(node_config(2));
+
+    sim.run_for(Duration::from_secs(1));
+    let dns_lookup_state = &sim.node(a).state().peers_dns_lookup["boot.sim:9732"];
+    assert!(matches!(
+        dns_lookup_state.status,
+        PeersDnsLookupStatus::Error { .. }
+    ));
+    assert_eq!(dns_lookup_state.failures, 1);
+
+    sim.node_mut(a)
+        .service
+        .dns
+        .records
+        .insert("boot.sim".to_owned(), vec![b.ip()]);
+
+    assert!(sim.run_until(Duration::from_secs(30), |sim| {
+        is_handshaked(sim, a, b) && is_handshaked(sim, b, a)
+    }));
+    assert_eq!(
+        sim.node(a).state().peers_dns_lookup["boot.sim:9732"].failures,
+        0
+    );
+}
+
+#[test]
+fn test_partition() {
+    let mut sim = Simulator::new(SimulatorConfig::default());
+    let a = sim.node_add(node_config(1));
+    let b = sim.node_add(node_config(2));
+
+    sim.partition(&[a], &[b]);
+    connect(&mut sim, a, b);
+
+    // connection hangs, until it's timed out.
+    let config = sim.node(a).state().config.clone();
+    sim.run_for(config.peer_connecting_timeout + Duration::from_secs(1));
+    assert!(matches!(
+        peer_status(&sim, a, b),
+        Some(PeerStatus::Potential)
+    ));
+    let attempts = &sim.node(a).state().peers[&b].connection_attempts;
+    assert_eq!(attempts.count, 1);
+    assert_eq!(attempts.last_error, Some(IOErrorKind::TimedOut));
+    assert!(sim.node(b).state().peers.is_empty());
+
+    // reconnects after the backoff.
+    sim.heal();
+    assert!(sim.run_until(config.peer_connection_backoff, |sim| {
+        is_handshaked(sim, a, b) && is_handshaked(sim, b, a)
+    }));
+}
+
+#[test]
+fn test_disconnect() {
+    let mut sim = Simulator::new(SimulatorConfig::default());
+    let a = sim.node_add(node_config(1));
+    let b = sim.node_add(node_config(2));
+
+    connect(&mut sim, a, b);
+    assert!(sim.run_until(Duration::from_secs(5), |sim| {
+        is_handshaked(sim, a, b) && is_handshaked(sim, b, a)
+    }));
+
+    sim.dispatch(a, PeerDisconnectAction { address: b }.into());
+
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        peer_status(sim, a, b).is_none() && peer_status(sim, b, a).is_none()
+    }));
+}
+
+fn block_header(level: i32, predecessor: BlockHash) -> BlockHeaderWithHash {
+    let header = BlockHeaderBuilder::default()
+        .level(level)
+        .proto(0)
+        .predecessor(predecessor)
+        .timestamp(level as i64)
+        .validation_pass(0)
+        .operations_hash(OperationListListHash::try_from(vec![0; 32]).unwrap())
+        .fitness(vec![])
+        .context(ContextHash::try_from(vec![0; 32]).unwrap())
+        .protocol_data(vec![])
+        .build()
+        .unwrap();
+    block_header_with_hash(header).unwrap()
+}
+
+fn genesis_hash() -> BlockHash {
+    BlockHash::try_from(vec![0; 32]).unwrap()
+}
+
+/// Make node send the message to the peer.
+fn send(sim: &mut Simulator, node: SocketAddr, peer: SocketAddr, message: PeerMessage) {
+    sim.dispatch(
+        node,
+        PeerMessageWriteInitAction {
+            address: peer,
+            message: message.into(),
+        }
+        .into(),
+    );
+}
+
+fn current_head_message(chain_id: ChainId, head: &BlockHeaderWithHash) -> PeerMessage {
+    PeerMessage::CurrentHead(CurrentHeadMessage::new(
+        chain_id,
+        (*head.header).clone(),
+        Mempool::default(),
+    ))
+}
+
+fn peer_head_level(sim: &Simulator, node: SocketAddr, peer: SocketAddr) -> Option<i32> {
+    sim.node(node)
+        .state()
+        .chain
+        .peers_heads
+        .get(&peer)
+        .map(|peer_head| peer_head.head.header.level())
+}
+
+#[test]
+fn test_current_head() {
+    let mut sim = Simulator::new(SimulatorConfig::default());
+    let a = sim.node_add(node_config(1));
+    let b = sim.node_add(node_config(2));
+    let chain_id = sim.node(a).state().config.chain_id.clone();
+    let other_chain_id = TezosNetwork::Mainnet.chain_id();
+
+    connect(&mut sim, a, b);
+    assert!(sim.run_until(Duration::from_secs(5), |sim| {
+        is_handshaked(sim, a, b) && is_handshaked(sim, b, a)
+    }));
+    // nodes ask each other for the current head once handshaked. They
+    // don't have any yet, so they answer with genesis.
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        peer_head_level(sim, a, b) == Some(0) && peer_head_level(sim, b, a) == Some(0)
+    }));
+    assert_eq!(
+        sim.node(a).state().chain.peers_heads[&b].head.hash,
+        sim.node(a).state().config.genesis_block_hash
+    );
+    assert_eq!(sim.node(a).state().chain.peers_ahead().count(), 0);
+
+    // head from another chain is ignored.
+    let head = block_header(1, genesis_hash());
+    send(
+        &mut sim,
+        b,
+        a,
+        current_head_message(other_chain_id.clone(), &head),
+    );
+    sim.run_for(Duration::from_secs(1));
+    assert_eq!(peer_head_level(&sim, a, b), Some(0));
+
+    send(
+        &mut sim,
+        b,
+        a,
+        current_head_message(chain_id.clone(), &head),
+    );
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        peer_head_level(sim, a, b) == Some(1)
+    }));
+    assert_eq!(sim.node(a).state().chain.peers_ahead().count(), 1);
+
+    // `a` downloads the announced head.
+    send(
+        &mut sim,
+        b,
+        a,
+        PeerMessage::BlockHeader(BlockHeaderMessage::from((*head.header).clone())),
+    );
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        sim.node(a).state().chain.current_head_level() == Some(1)
+    }));
+    assert_eq!(sim.node(a).state().chain.peers_ahead().count(), 0);
+
+    // `a` answers `GetCurrentHead` only for its own chain.
+    send(
+        &mut sim,
+        b,
+        a,
+        PeerMessage::GetCurrentHead(GetCurrentHeadMessage::new(other_chain_id)),
+    );
+    sim.run_for(Duration::from_secs(1));
+    assert_eq!(peer_head_level(&sim, b, a), Some(0));
+
+    send(
+        &mut sim,
+        b,
+        a,
+        PeerMessage::GetCurrentHead(GetCurrentHeadMessage::new(chain_id)),
+    );
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        peer_head_level(sim, b, a) == Some(1)
+    }));
+}
+
+fn handshake_all(sim: &mut Simulator, node: SocketAddr, peers: &[SocketAddr]) {
+    for peer in peers {
+        connect(sim, node, *peer);
+    }
+    assert!(sim.run_until(Duration::from_secs(5), |sim| {
+        peers
+            .iter()
+            .all(|peer| is_handshaked(sim, node, *peer) && is_handshaked(sim, *peer, node))
+    }));
+}
+
+/// Peers, which have an outstanding block headers request from `node`.
+fn requested_from(sim: &Simulator, node: SocketAddr) -> Vec<SocketAddr> {
+    let block_headers = &sim.node(node).state().chain.block_headers;
+    block_headers.requests.keys().cloned().collect()
+}
+
+#[test]
+fn test_block_headers_download_from_announcing_peer() {
+    let mut sim = Simulator::new(SimulatorConfig::default());
+    let a = sim.node_add(node_config(1));
+    let b = sim.node_add(node_config(2));
+    let c = sim.node_add(node_config(3));
+    let chain_id = sim.node(a).state().config.chain_id.clone();
+    handshake_all(&mut sim, a, &[b, c]);
+
+    let head_1 = block_header(1, genesis_hash());
+    let head_2 = block_header(2, head_1.hash.clone());
+    send(&mut sim, b, a, current_head_message(chain_id, &head_2));
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        !sim.node(a).state().chain.block_headers.requests.is_empty()
+    }));
+
+    // only `b` announced the head, so `c` isn't asked for it.
+    let block_headers = &sim.node(a).state().chain.block_headers;
+    assert_eq!(requested_from(&sim, a), vec![b]);
+    assert_eq!(block_headers.requests[&b].hash, head_2.hash);
+    let download = block_headers.download.as_ref().unwrap();
+    assert_eq!(download.sources.iter().collect::<Vec<_>>(), vec![&b]);
+
+    // download is dropped, once its only source disconnects.
+    sim.dispatch(a, PeerDisconnectAction { address: b }.into());
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        sim.node(a).state().chain.block_headers.download.is_none()
+    }));
+    assert!(requested_from(&sim, a).is_empty());
+}
+
+#[test]
+fn test_block_headers_download_fork_dropped() {
+    let mut sim = Simulator::new(SimulatorConfig::default());
+    let a = sim.node_add(node_config(1));
+    let b = sim.node_add(node_config(2));
+    let chain_id = sim.node(a).state().config.chain_id.clone();
+    handshake_all(&mut sim, a, &[b]);
+
+    let head_1 = block_header(1, genesis_hash());
+    send(
+        &mut sim,
+        b,
+        a,
+        current_head_message(chain_id.clone(), &head_1),
+    );
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        !sim.node(a).state().chain.block_headers.requests.is_empty()
+    }));
+    let message = BlockHeaderMessage::from((*head_1.header).clone());
+    send(&mut sim, b, a, PeerMessage::BlockHeader(message));
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        sim.node(a).state().chain.current_head_level() == Some(1)
+    }));
+
+    // `b` switches to the fork, which doesn't include our head.
+    let fork_1 = block_header(1, BlockHash::try_from(vec![1; 32]).unwrap());
+    let fork_2 = block_header(2, fork_1.hash.clone());
+    send(&mut sim, b, a, current_head_message(chain_id, &fork_2));
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        !sim.node(a).state().chain.block_headers.requests.is_empty()
+    }));
+    let message = BlockHeaderMessage::from((*fork_2.header).clone());
+    send(&mut sim, b, a, PeerMessage::BlockHeader(message));
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        sim.node(a).state().chain.block_headers.download.is_none()
+    }));
+
+    let current_head = sim.node(a).state().chain.current_head.as_ref().unwrap();
+    assert_eq!(current_head.hash, head_1.hash);
+}
+
+#[test]
+fn test_block_headers_download_source_timeout() {
+    let mut sim = Simulator::new(SimulatorConfig::default());
+    let a = sim.node_add(node_config(1));
+    let b = sim.node_add(node_config(2));
+    let c = sim.node_add(node_config(3));
+    let chain_id = sim.node(a).state().config.chain_id.clone();
+    handshake_all(&mut sim, a, &[b, c]);
+
+    let head = block_header(1, genesis_hash());
+    send(
+        &mut sim,
+        b,
+        a,
+        current_head_message(chain_id.clone(), &head),
+    );
+    send(&mut sim, c, a, current_head_message(chain_id, &head));
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        let download = sim.node(a).state().chain.block_headers.download.as_ref();
+        download.map_or(false, |download| download.sources.len() == 2)
+    }));
+    let silent = match requested_from(&sim, a).as_slice() {
+        [v] => *v,
+        requested => panic!("unexpected requests: {:?}", requested),
+    };
+    let other = if silent == b { c } else { b };
+
+    // neither peer answers the requests, so once the first one times
+    // out, the other source is asked and the silent one isn't asked again.
+    let timeout = sim.node(a).state().config.chain_block_headers_get_timeout;
+    assert!(sim.run_until(timeout + Duration::from_secs(2), |sim| {
+        requested_from(sim, a) == vec![other]
+    }));
+    let download = sim.node(a).state().chain.block_headers.download.as_ref();
+    assert!(!download.unwrap().sources.contains(&silent));
+
+    // download is dropped, once all sources time out.
+    assert!(sim.run_until(timeout + Duration::from_secs(2), |sim| {
+        sim.node(a).state().chain.block_headers.download.is_none()
+    }));
+    assert!(requested_from(&sim, a).is_empty());
+}
+
+#[test]
+fn test_block_headers_download_higher_head() {
+    let mut sim = Simulator::new(SimulatorConfig::default());
+    let a = sim.node_add(node_config(1));
+    let b = sim.node_add(node_config(2));
+    let c = sim.node_add(node_config(3));
+    let chain_id = sim.node(a).state().config.chain_id.clone();
+    handshake_all(&mut sim, a, &[b, c]);
+
+    let head_1 = block_header(1, genesis_hash());
+    let head_2 = block_header(2, head_1.hash.clone());
+    send(
+        &mut sim,
+        b,
+        a,
+        current_head_message(chain_id.clone(), &head_1),
+    );
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        requested_from(sim, a) == vec![b]
+    }));
+
+    // `c` announces the higher head during the download and becomes
+    // its source.
+    send(&mut sim, c, a, current_head_message(chain_id, &head_2));
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        let download = sim.node(a).state().chain.block_headers.download.as_ref();
+        download.map_or(false, |download| download.sources.contains(&c))
+    }));
+
+    // once `head_1` is downloaded, `a` continues with `head_2`.
+    let message = BlockHeaderMessage::from((*head_1.header).clone());
+    send(&mut sim, b, a, PeerMessage::BlockHeader(message));
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        let block_headers = &sim.node(a).state().chain.block_headers;
+        block_headers.is_requested(&c, &head_2.hash)
+    }));
+    assert_eq!(sim.node(a).state().chain.current_head_level(), Some(1));
+
+    let message = BlockHeaderMessage::from((*head_2.header).clone());
+    send(&mut sim, c, a, PeerMessage::BlockHeader(message));
+    assert!(sim.run_until(Duration::from_secs(1), |sim| {
+        sim.node(a).state().chain.current_head_level() == Some(2)
+    }));
+}
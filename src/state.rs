@@ -1,24 +1,44 @@
 use redux_rs::ActionId;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
+use std::time::SystemTime;
 
 use ::storage::persistent::BincodeEncoded;
+use crypto::hash::CryptoboxPublicKeyHash;
 
+use crate::chain::ChainState;
 use crate::config::Config;
 use crate::peer::connection::incoming::accept::PeerConnectionIncomingAcceptState;
-use crate::peer::Peer;
+use crate::peer::connection::PeerConnectionState;
+use crate::peer::handshaking::PeerHandshaking;
+use crate::peer::score::PeerScore;
+use crate::peer::{Peer, PeerHandshaked, PeerStatus};
 use crate::peers::dns_lookup::PeersDnsLookupState;
+use crate::peers::graylist::{graylist_ip_canonical, PeerGraylistState};
 use crate::storage::StorageState;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct State {
     pub config: Config,
     pub peers: BTreeMap<SocketAddr, Peer>,
-    pub peers_dns_lookup: Option<PeersDnsLookupState>,
+    /// Dns lookups of the bootstrap peers, keyed by
+    /// [crate::peers::dns_lookup::peers_dns_lookup_key].
+    pub peers_dns_lookup: BTreeMap<String, PeersDnsLookupState>,
+    pub peers_graylist: BTreeMap<IpAddr, PeerGraylistState>,
+    /// Reputation of the peers (see [crate::peer::score]), by their
+    /// listening address. Kept separately from `peers`, so that it
+    /// outlives the peer's connection.
+    pub peers_scores: BTreeMap<SocketAddr, PeerScore>,
     pub peer_connection_incoming_accept: PeerConnectionIncomingAcceptState,
+    pub chain: ChainState,
     pub storage: StorageState,
     pub last_action_id: ActionId,
+
+    /// Time of the last [crate::tick::TickAction].
+    ///
+    /// Reducers use it as the current time.
+    pub time: SystemTime,
 }
 
 impl State {
@@ -26,12 +46,213 @@ impl State {
         Self {
             config,
             peers: BTreeMap::new(),
-            peers_dns_lookup: None,
+            peers_dns_lookup: BTreeMap::new(),
+            peers_graylist: BTreeMap::new(),
+            peers_scores: BTreeMap::new(),
             peer_connection_incoming_accept: PeerConnectionIncomingAcceptState::Idle,
+            chain: ChainState::new(),
             storage: StorageState::new(),
             last_action_id: ActionId::ZERO,
+            time: SystemTime::UNIX_EPOCH,
+        }
+    }
+
+    /// Number of potential peers, which we aren't connected to.
+    pub fn peers_potential_len(&self) -> usize {
+        self.peers
+            .values()
+            .filter(|peer| matches!(&peer.status, PeerStatus::Potential))
+            .count()
+    }
+
+    /// Number of peers, which are connecting or handshaking.
+    pub fn peers_pending_len(&self) -> usize {
+        self.peers
+            .values()
+            .filter(|peer| {
+                matches!(
+                    &peer.status,
+                    PeerStatus::Connecting(_) | PeerStatus::Handshaking(_)
+                )
+            })
+            .count()
+    }
+
+    /// Number of connected peers, including pending ones.
+    pub fn peers_connected_len(&self) -> usize {
+        self.peers
+            .values()
+            .filter(|peer| {
+                matches!(
+                    &peer.status,
+                    PeerStatus::Connecting(_)
+                        | PeerStatus::Handshaking(_)
+                        | PeerStatus::Handshaked(_)
+                )
+            })
+            .count()
+    }
+
+    /// Number of connected peers (including pending ones), which
+    /// connected to us.
+    pub fn peers_incoming_len(&self) -> usize {
+        self.peers
+            .values()
+            .filter(|peer| match &peer.status {
+                PeerStatus::Connecting(PeerConnectionState::Incoming(_)) => true,
+                PeerStatus::Handshaking(PeerHandshaking { incoming, .. })
+                | PeerStatus::Handshaked(PeerHandshaked { incoming, .. }) => *incoming,
+                _ => false,
+            })
+            .count()
+    }
+
+    /// Address on which the peer listens for incoming connections.
+    ///
+    /// For incoming connections, port is known only once we receive
+    /// peer's connection message. Until then it's the connection's address.
+    pub fn peer_listen_address(&self, address: SocketAddr) -> SocketAddr {
+        let port = self
+            .peers
+            .get(&address)
+            .and_then(|peer| match &peer.status {
+                PeerStatus::Handshaking(handshaking) => handshaking
+                    .status
+                    .remote_connection_message()
+                    .map(|message| message.port),
+                PeerStatus::Handshaked(handshaked) => Some(handshaked.port),
+                _ => None,
+            });
+        match port {
+            Some(port) => SocketAddr::new(address.ip(), port),
+            None => address,
         }
     }
+
+    /// Whether we are handshaking or handshaked with the peer, which
+    /// listens on this address, through another connection (e.g. the
+    /// incoming one).
+    pub fn is_listen_address_connected(&self, address: &SocketAddr) -> bool {
+        self.peers.iter().any(|(peer_address, peer)| {
+            peer_address != address
+                && matches!(
+                    &peer.status,
+                    PeerStatus::Handshaking(_) | PeerStatus::Handshaked(_)
+                )
+                && &self.peer_listen_address(*peer_address) == address
+        })
+    }
+
+    /// Address under which peer's score is kept.
+    ///
+    /// `None` for the incoming peer, until it tells us its listening
+    /// port. Scoring it by the connection's random port would only
+    /// leave behind entries no one will ever look up again.
+    pub fn peer_score_address(&self, address: &SocketAddr) -> Option<SocketAddr> {
+        let peer = match self.peers.get(address) {
+            Some(v) => v,
+            None => return Some(*address),
+        };
+        match &peer.status {
+            PeerStatus::Connecting(PeerConnectionState::Incoming(_)) => return None,
+            PeerStatus::Handshaking(handshaking)
+                if handshaking.incoming
+                    && handshaking.status.remote_connection_message().is_none() =>
+            {
+                return None
+            }
+            _ => {}
+        }
+        Some(self.peer_listen_address(*address))
+    }
+
+    pub fn peer_score(&self, address: &SocketAddr) -> PeerScore {
+        self.peer_score_address(address)
+            .and_then(|address| self.peers_scores.get(&address))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Whether peer's ip is graylisted.
+    pub fn is_graylisted(&self, address: &SocketAddr) -> bool {
+        self.peers_graylist
+            .contains_key(&graylist_ip_canonical(address.ip()))
+    }
+
+    /// Whether ip belongs to one of the `config.trusted_peers`.
+    ///
+    /// Only ip is compared, since incoming connections come from
+    /// a random port.
+    pub fn is_trusted_ip(&self, ip: IpAddr) -> bool {
+        self.config
+            .trusted_peers
+            .iter()
+            .any(|trusted| trusted.address.ip() == ip)
+    }
+
+    /// Whether incoming connection from the ip is exempt from the peer
+    /// thresholds.
+    ///
+    /// Port and identity of the incoming peer aren't known before the
+    /// handshake, so any peer from the trusted ip is exempt, but only
+    /// as many incoming connections from that ip as there are trusted
+    /// peers on it.
+    pub fn is_trusted_ip_exempt(&self, ip: IpAddr) -> bool {
+        let trusted_len = self
+            .config
+            .trusted_peers
+            .iter()
+            .filter(|trusted| trusted.address.ip() == ip)
+            .count();
+        let incoming_len = self
+            .peers
+            .iter()
+            .filter(|(address, _)| address.ip() == ip)
+            .filter(|(_, peer)| match &peer.status {
+                PeerStatus::Connecting(PeerConnectionState::Incoming(_)) => true,
+                PeerStatus::Handshaking(PeerHandshaking { incoming, .. })
+                | PeerStatus::Handshaked(PeerHandshaked { incoming, .. }) => *incoming,
+                _ => false,
+            })
+            .count();
+        incoming_len < trusted_len
+    }
+
+    /// Whether address is one of the `config.trusted_peers`.
+    pub fn is_trusted_address(&self, address: &SocketAddr) -> bool {
+        self.config
+            .trusted_peers
+            .iter()
+            .any(|trusted| &trusted.address == address)
+    }
+
+    /// Whether peer with the trusted ip has an identity different
+    /// from the one configured for that ip.
+    pub fn is_trusted_peer_id_mismatch(
+        &self,
+        ip: IpAddr,
+        peer_id: &CryptoboxPublicKeyHash,
+    ) -> bool {
+        let mut trusted = self
+            .config
+            .trusted_peers
+            .iter()
+            .filter(|trusted| trusted.address.ip() == ip)
+            .peekable();
+        trusted.peek().is_some()
+            && trusted.all(|trusted| {
+                trusted
+                    .peer_id
+                    .as_ref()
+                    .map_or(false, |trusted_id| trusted_id != peer_id)
+            })
+    }
+
+    /// Whether peer thresholds allow new (incoming or outgoing) connection.
+    pub fn peers_can_connect(&self) -> bool {
+        self.peers_connected_len() < self.config.peers_connected_max
+            && self.peers_pending_len() < self.config.peers_pending_max
+    }
 }
 
 impl BincodeEncoded for State {}
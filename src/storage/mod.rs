@@ -4,4 +4,5 @@ pub use storage_state::*;
 pub mod request;
 
 pub mod block_header;
+pub mod peers;
 pub mod state_snapshot;
@@ -0,0 +1,14 @@
+mod storage_peers_state;
+pub use storage_peers_state::*;
+
+mod storage_peers_actions;
+pub use storage_peers_actions::*;
+
+mod storage_peers_reducer;
+pub use storage_peers_reducer::*;
+
+mod storage_peers_effects;
+pub use storage_peers_effects::*;
+
+#[cfg(test)]
+mod storage_peers_tests;
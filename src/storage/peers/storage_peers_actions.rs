@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+/// Load known peers from the storage.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoragePeersLoadAction {}
+
+/// Store (insert or update) the peers.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoragePeersPutAction {
+    pub addresses: Vec<SocketAddr>,
+}
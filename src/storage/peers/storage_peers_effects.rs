@@ -0,0 +1,102 @@
+use redux_rs::{ActionWithId, Store};
+
+use crate::action::Action;
+use crate::peer::PeerStatus;
+use crate::peers::add::multi::PeersAddMultiAction;
+use crate::peers::graylist::graylist_ip_canonical;
+use crate::service::storage_service::{StorageRequestPayload, StorageResponseSuccess};
+use crate::service::Service;
+use crate::storage::request::StorageRequestCreateAction;
+use crate::State;
+
+use super::{StoragePeersPutAction, StoredPeer};
+
+pub fn storage_peers_effects<S>(store: &mut Store<State, S, Action>, action: &ActionWithId<Action>)
+where
+    S: Service,
+{
+    match &action.action {
+        Action::Tick(_) => {
+            let state = store.state.get();
+            let should_put = state.storage.peers_put_at.map_or(true, |put_at| {
+                state.time >= put_at + state.config.peers_put_interval
+            });
+            if !should_put {
+                return;
+            }
+            let addresses = state
+                .peers
+                .iter()
+                .filter(|(_, peer)| matches!(&peer.status, PeerStatus::Handshaked(_)))
+                .map(|(address, _)| *address)
+                .collect();
+            store.dispatch(StoragePeersPutAction { addresses }.into());
+        }
+        Action::PeersGraylistAddress(action) => {
+            // persist the ban.
+            store.dispatch(
+                StoragePeersPutAction {
+                    addresses: vec![action.address],
+                }
+                .into(),
+            );
+        }
+        Action::StoragePeersLoad(_) => {
+            store.dispatch(
+                StorageRequestCreateAction {
+                    payload: StorageRequestPayload::PeersGet,
+                }
+                .into(),
+            );
+        }
+        Action::StoragePeersPut(action) => {
+            let state = store.state.get();
+            let peers = action
+                .addresses
+                .iter()
+                .filter(|address| state.peers.contains_key(address))
+                .map(|address| {
+                    let address = state.peer_listen_address(*address);
+                    StoredPeer {
+                        address,
+                        last_seen: state.time,
+                        score: state.peer_score(&address),
+                        graylist: state
+                            .peers_graylist
+                            .get(&graylist_ip_canonical(address.ip()))
+                            .cloned(),
+                    }
+                })
+                .collect::<Vec<_>>();
+            if peers.is_empty() {
+                return;
+            }
+            let time = state.time;
+            store.dispatch(
+                StorageRequestCreateAction {
+                    payload: StorageRequestPayload::PeersPut(peers, time),
+                }
+                .into(),
+            );
+        }
+        Action::StorageRequestSuccess(action) => {
+            if let StorageResponseSuccess::PeersGetSuccess(peers) = &action.result {
+                let state = store.state.get();
+                // graylist is already restored by the reducer.
+                let mut peers = peers
+                    .iter()
+                    .filter(|peer| !state.is_graylisted(&peer.address))
+                    .collect::<Vec<_>>();
+                // number of potential peers is limited, so add the best ones first.
+                peers.sort_by(|a, b| {
+                    b.score
+                        .cmp(&a.score)
+                        .then_with(|| b.last_seen.cmp(&a.last_seen))
+                });
+                let addresses = peers.into_iter().map(|peer| peer.address).collect();
+                store.dispatch(PeersAddMultiAction { addresses }.into());
+            }
+        }
+        _ => {}
+    }
+}
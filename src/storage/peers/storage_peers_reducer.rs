@@ -0,0 +1,30 @@
+use redux_rs::ActionWithId;
+
+use crate::peers::graylist::graylist_ip_canonical;
+use crate::service::storage_service::StorageResponseSuccess;
+use crate::{action::Action, State};
+
+pub fn storage_peers_reducer(state: &mut State, action: &ActionWithId<Action>) {
+    match &action.action {
+        Action::StoragePeersPut(_) => {
+            state.storage.peers_put_at = Some(state.time);
+        }
+        Action::StorageRequestSuccess(action) => {
+            if let StorageResponseSuccess::PeersGetSuccess(peers) = &action.result {
+                let time = state.time;
+                for peer in peers.iter() {
+                    if peer.score != 0 {
+                        state.peers_scores.entry(peer.address).or_insert(peer.score);
+                    }
+                    if let Some(graylist) = peer.graylist.as_ref().filter(|x| x.until > time) {
+                        state
+                            .peers_graylist
+                            .entry(graylist_ip_canonical(peer.address.ip()))
+                            .or_insert_with(|| graylist.clone());
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::time::SystemTime;
+
+use crate::peer::score::PeerScore;
+use crate::peers::graylist::PeerGraylistState;
+
+/// Known peer, persisted across restarts.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoredPeer {
+    /// Peer's listening address.
+    pub address: SocketAddr,
+    /// Last time we were connected to the peer.
+    pub last_seen: SystemTime,
+    pub score: PeerScore,
+    /// Set if peer was graylisted at the time it was stored.
+    pub graylist: Option<PeerGraylistState>,
+}
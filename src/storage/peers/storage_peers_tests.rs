@@ -0,0 +1,59 @@
+//! Loading stored peers, driven with `store.dispatch` over mocked services.
+
+use std::time::Duration;
+
+use crate::event::WakeupEvent;
+use crate::peers::graylist::{PeerGraylistReason, PeerGraylistState};
+use crate::service::mock::{address, node};
+use crate::service::storage_service::{StorageResponse, StorageResponseSuccess};
+use crate::storage::peers::{StoragePeersLoadAction, StoredPeer};
+
+#[test]
+fn test_stored_peers_load() {
+    let mut store = node(address(1));
+    let time = store.state().time;
+    let stored_peer = |last_octet, score, graylist| StoredPeer {
+        address: address(last_octet),
+        last_seen: time,
+        score,
+        graylist,
+    };
+    let graylist = |until| PeerGraylistState {
+        reason: PeerGraylistReason::LowScore(-100),
+        since: time,
+        until,
+    };
+
+    store.dispatch(StoragePeersLoadAction {}.into());
+    let req_id = store.state().storage.requests.last_added_req_id();
+    store
+        .service
+        .storage
+        .responses
+        .push_back(StorageResponse::new(
+            req_id,
+            Ok(StorageResponseSuccess::PeersGetSuccess(vec![
+                stored_peer(2, -30, None),
+                stored_peer(3, 0, Some(graylist(time + Duration::from_secs(60)))),
+                // graylist entry has already expired.
+                stored_peer(4, 0, Some(graylist(time))),
+            ])),
+        ));
+    store.dispatch(WakeupEvent {}.into());
+
+    // banned peer isn't added, but its graylist entry is restored.
+    assert!(store.state().peers.contains_key(&address(2)));
+    assert!(!store.state().peers.contains_key(&address(3)));
+    assert_eq!(
+        store
+            .state()
+            .peers_graylist
+            .get(&address(3).ip())
+            .map(|x| x.until),
+        Some(time + Duration::from_secs(60))
+    );
+    assert!(store.state().peers.contains_key(&address(4)));
+    assert!(!store.state().is_graylisted(&address(4)));
+    // score is restored.
+    assert_eq!(store.state().peer_score(&address(2)), -30);
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::time::SystemTime;
 
 use crate::request::PendingRequests;
 use crate::storage::block_header::put::StorageBlockHeaderPutState;
@@ -9,6 +10,8 @@ use crate::storage::request::StorageRequestState;
 pub struct StorageState {
     pub block_headers_put: VecDeque<StorageBlockHeaderPutState>,
     pub requests: PendingRequests<StorageRequestState>,
+    /// Last time peers were put in the storage.
+    pub peers_put_at: Option<SystemTime>,
 }
 
 impl StorageState {
@@ -16,6 +19,7 @@ impl StorageState {
         Self {
             block_headers_put: VecDeque::new(),
             requests: PendingRequests::new(),
+            peers_put_at: None,
         }
     }
 }
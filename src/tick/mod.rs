@@ -0,0 +1,8 @@
+mod tick_actions;
+pub use tick_actions::*;
+
+mod tick_reducer;
+pub use tick_reducer::*;
+
+mod tick_effects;
+pub use tick_effects::*;
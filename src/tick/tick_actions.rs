@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime};
+
+/// Interval in which [TickAction] is dispatched from the mio loop,
+/// while there are events to be processed.
+pub const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Interval in which [TickAction] is dispatched from the mio loop,
+/// while there are no events, so that idle node doesn't fill the
+/// action log with ticks.
+pub const TICK_IDLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Periodic action, which updates current time in the state and
+/// triggers time based checks (like timeouts).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TickAction {
+    pub time: SystemTime,
+}
@@ -0,0 +1,38 @@
+use redux_rs::{ActionWithId, Store};
+
+use crate::peer::connection::outgoing::PeerConnectionOutgoingRandomInitAction;
+use crate::peer::PeerStatus;
+use crate::peers::timeouts::PeersTimeoutsCheckAction;
+use crate::service::Service;
+use crate::{action::Action, State};
+
+pub fn tick_effects<S: Service>(
+    store: &mut Store<State, S, Action>,
+    action: &ActionWithId<Action>,
+) {
+    match &action.action {
+        Action::Tick(_) => {
+            let state = store.state.get();
+            let has_pending = state.peers.values().any(|peer| {
+                matches!(
+                    &peer.status,
+                    PeerStatus::Connecting(_) | PeerStatus::Handshaking(_)
+                )
+            });
+            if has_pending {
+                store.dispatch(PeersTimeoutsCheckAction {}.into());
+            }
+
+            let state = store.state.get();
+            let trusted_potential = state.peers.iter().any(|(address, peer)| {
+                matches!(&peer.status, PeerStatus::Potential)
+                    && state.is_trusted_address(address)
+                    && !state.is_listen_address_connected(address)
+            });
+            if trusted_potential || state.peers_connected_len() < state.config.peers_connected_min {
+                store.dispatch(PeerConnectionOutgoingRandomInitAction {}.into());
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,12 @@
+use redux_rs::ActionWithId;
+
+use crate::{action::Action, State};
+
+pub fn tick_reducer(state: &mut State, action: &ActionWithId<Action>) {
+    match &action.action {
+        Action::Tick(action) => {
+            state.time = action.time;
+        }
+        _ => {}
+    }
+}
@@ -1,2 +1,3 @@
 pub mod chunking;
+pub mod peers_storage;
 pub mod persistent_storage;
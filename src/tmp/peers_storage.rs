@@ -0,0 +1,129 @@
+//! Temporary file.
+//!
+//! `storage` crate doesn't have a dedicated storage for the peers yet,
+//! so known peers are stored as a single value in our own column
+//! ([PEERS_COLUMN]) of the main rocksdb database.
+
+use rocksdb::{ColumnFamilyDescriptor, Options, DB};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use crate::storage::peers::StoredPeer;
+
+pub const PEERS_COLUMN: &str = "redux_peers";
+const PEERS_KEY: &[u8] = b"peers";
+
+/// Max number of stored peers.
+pub const STORED_PEERS_MAX: usize = 1000;
+/// Peers, which we haven't seen for this long, are pruned.
+pub const STORED_PEER_TTL: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+#[derive(Debug)]
+pub enum PeersStorageError {
+    ColumnMissing,
+    Db(rocksdb::Error),
+    Encoding(serde_json::Error),
+}
+
+impl From<rocksdb::Error> for PeersStorageError {
+    fn from(error: rocksdb::Error) -> Self {
+        Self::Db(error)
+    }
+}
+
+impl From<serde_json::Error> for PeersStorageError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Encoding(error)
+    }
+}
+
+#[derive(Clone)]
+pub struct PeersStorage {
+    db: Arc<DB>,
+}
+
+impl PeersStorage {
+    pub fn descriptor() -> ColumnFamilyDescriptor {
+        ColumnFamilyDescriptor::new(PEERS_COLUMN, Options::default())
+    }
+
+    /// `db` must be opened with [Self::descriptor] column.
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { db }
+    }
+
+    pub fn get(&self) -> Result<Vec<StoredPeer>, PeersStorageError> {
+        let column = self
+            .db
+            .cf_handle(PEERS_COLUMN)
+            .ok_or(PeersStorageError::ColumnMissing)?;
+        match self.db.get_cf(column, PEERS_KEY)? {
+            Some(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Insert or update the peers and prune the stale ones.
+    ///
+    /// `time` is the current time of the state machine.
+    pub fn put(&self, peers: Vec<StoredPeer>, time: SystemTime) -> Result<(), PeersStorageError> {
+        let stored = self
+            .get()?
+            .into_iter()
+            .chain(peers)
+            .map(|peer| (peer.address, peer));
+        // later entries override the earlier ones.
+        let stored = stored
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(_, peer)| peer)
+            .collect();
+        let stored = serde_json::to_vec(&stored_peers_prune(stored, time))?;
+
+        let column = self
+            .db
+            .cf_handle(PEERS_COLUMN)
+            .ok_or(PeersStorageError::ColumnMissing)?;
+        Ok(self.db.put_cf(column, PEERS_KEY, stored)?)
+    }
+}
+
+/// Remove peers we haven't seen for [STORED_PEER_TTL] since `time`
+/// and keep at most [STORED_PEERS_MAX] best (by score, then by last
+/// seen) ones.
+pub fn stored_peers_prune(mut peers: Vec<StoredPeer>, time: SystemTime) -> Vec<StoredPeer> {
+    peers.retain(|peer| peer.last_seen + STORED_PEER_TTL >= time);
+    peers.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| b.last_seen.cmp(&a.last_seen))
+    });
+    peers.truncate(STORED_PEERS_MAX);
+    peers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_peers_prune_by_time() {
+        let time = SystemTime::UNIX_EPOCH + STORED_PEER_TTL * 2;
+        let stored_peer = |last_octet, last_seen| StoredPeer {
+            address: ([10, 0, 0, last_octet], 9732).into(),
+            last_seen,
+            score: 0,
+            graylist: None,
+        };
+        // all peers are stale, even though one of them is the latest stored.
+        let peers = vec![
+            stored_peer(1, time - STORED_PEER_TTL - Duration::from_secs(1)),
+            stored_peer(2, time - STORED_PEER_TTL * 2),
+        ];
+        assert!(stored_peers_prune(peers, time).is_empty());
+
+        let peers = vec![stored_peer(1, time - STORED_PEER_TTL)];
+        assert_eq!(stored_peers_prune(peers, time).len(), 1);
+    }
+}